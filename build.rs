@@ -0,0 +1,32 @@
+use std::process::Command;
+
+/// Embeds the commit and build time reported by `GET /version`.
+///
+/// `GIT_SHA` (or Railway's `RAILWAY_GIT_COMMIT_SHA`) wins over asking git, for builds made
+/// from a source tarball without `.git`.
+fn main() {
+    let sha = std::env::var("GIT_SHA")
+        .or_else(|_| std::env::var("RAILWAY_GIT_COMMIT_SHA"))
+        .ok()
+        .or_else(|| git(&["rev-parse", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=GIT_SHA={}", sha);
+    println!("cargo:rustc-env=BUILD_TIME={}", chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-env-changed=RAILWAY_GIT_COMMIT_SHA");
+    // A new commit moves HEAD itself (detached) or the branch it points to
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    if let Some(reference) = git(&["symbolic-ref", "-q", "HEAD"]) {
+        println!("cargo:rerun-if-changed=.git/{}", reference);
+    }
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
@@ -0,0 +1,33 @@
+CREATE TABLE IF NOT EXISTS projects (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    description TEXT NOT NULL,
+    images TEXT[] NOT NULL,
+    category VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Databases created with the old setup_projects_table_v2.sql store images as JSONB,
+-- which the repository cannot bind. Convert them to TEXT[] in place.
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = current_schema()
+          AND table_name = 'projects'
+          AND column_name = 'images'
+          AND data_type = 'jsonb'
+    ) THEN
+        DROP INDEX IF EXISTS idx_projects_images;
+        ALTER TABLE projects ADD COLUMN images_text TEXT[] NOT NULL DEFAULT '{}';
+        UPDATE projects
+        SET images_text = ARRAY(SELECT jsonb_array_elements_text(images));
+        ALTER TABLE projects DROP COLUMN images;
+        ALTER TABLE projects RENAME COLUMN images_text TO images;
+        ALTER TABLE projects ALTER COLUMN images DROP DEFAULT;
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_projects_category ON projects(category);
+CREATE INDEX IF NOT EXISTS idx_projects_created_at ON projects(created_at);
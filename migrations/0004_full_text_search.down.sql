@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_employees_search;
+ALTER TABLE employees DROP COLUMN IF EXISTS search_vector;
+
+DROP INDEX IF EXISTS idx_projects_search;
+ALTER TABLE projects DROP COLUMN IF EXISTS search_vector;
@@ -0,0 +1,2 @@
+-- Map of image URL -> { rendition name -> { url, width, height } }
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS renditions JSONB NOT NULL DEFAULT '{}'::jsonb;
@@ -0,0 +1,17 @@
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS images TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS renditions JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+UPDATE projects p
+SET images = coalesce(
+        (SELECT array_agg(i.url ORDER BY i.position) FROM project_images i WHERE i.project_id = p.id),
+        '{}'
+    ),
+    renditions = coalesce(
+        (SELECT jsonb_object_agg(i.url, i.renditions) FILTER (WHERE i.renditions <> '{}'::jsonb)
+         FROM project_images i WHERE i.project_id = p.id),
+        '{}'::jsonb
+    );
+
+ALTER TABLE projects ALTER COLUMN images DROP DEFAULT;
+
+DROP TABLE IF EXISTS project_images;
@@ -0,0 +1,34 @@
+CREATE TABLE IF NOT EXISTS project_images (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    alt TEXT,
+    caption TEXT,
+    position INTEGER NOT NULL,
+    is_cover BOOLEAN NOT NULL DEFAULT FALSE,
+    width INTEGER,
+    height INTEGER,
+    blurhash TEXT,
+    -- { rendition name -> { url, width, height } }
+    renditions JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_images_project ON project_images(project_id, position);
+-- At most one cover per project
+CREATE UNIQUE INDEX IF NOT EXISTS idx_project_images_cover ON project_images(project_id) WHERE is_cover;
+
+-- Move the URL array and rendition map into rows; the first image becomes the cover.
+INSERT INTO project_images (project_id, url, position, is_cover, width, height, renditions)
+SELECT p.id,
+       img.url,
+       (img.ord - 1)::int,
+       img.ord = 1,
+       (p.renditions -> img.url -> 'original' ->> 'width')::int,
+       (p.renditions -> img.url -> 'original' ->> 'height')::int,
+       coalesce(p.renditions -> img.url, '{}'::jsonb)
+FROM projects p
+CROSS JOIN LATERAL unnest(p.images) WITH ORDINALITY AS img(url, ord);
+
+ALTER TABLE projects DROP COLUMN images;
+ALTER TABLE projects DROP COLUMN renditions;
@@ -0,0 +1,19 @@
+-- Existing values were written with CURRENT_TIMESTAMP in the session time zone, which is
+-- exactly how a plain TIMESTAMP -> TIMESTAMPTZ conversion interprets them.
+UPDATE projects SET created_at = COALESCE(created_at, CURRENT_TIMESTAMP), updated_at = COALESCE(updated_at, created_at, CURRENT_TIMESTAMP);
+UPDATE employees SET created_at = COALESCE(created_at, CURRENT_TIMESTAMP), updated_at = COALESCE(updated_at, created_at, CURRENT_TIMESTAMP);
+
+ALTER TABLE projects
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ,
+    ALTER COLUMN created_at SET NOT NULL,
+    ALTER COLUMN updated_at TYPE TIMESTAMPTZ,
+    ALTER COLUMN updated_at SET NOT NULL;
+
+ALTER TABLE employees
+    ALTER COLUMN created_at TYPE TIMESTAMPTZ,
+    ALTER COLUMN created_at SET NOT NULL,
+    ALTER COLUMN updated_at TYPE TIMESTAMPTZ,
+    ALTER COLUMN updated_at SET NOT NULL;
+
+ALTER TABLE users ALTER COLUMN created_at TYPE TIMESTAMPTZ;
+ALTER TABLE project_images ALTER COLUMN created_at TYPE TIMESTAMPTZ;
@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP CONSTRAINT IF EXISTS fk_projects_category;
+DROP TABLE IF EXISTS categories;
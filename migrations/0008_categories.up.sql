@@ -0,0 +1,30 @@
+CREATE TABLE IF NOT EXISTS categories (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    slug VARCHAR(100) NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    icon VARCHAR(255),
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_categories_sort ON categories(sort_order, name);
+
+-- The categories the frontend has been using so far
+INSERT INTO categories (slug, name, sort_order) VALUES
+    ('web_development', 'Web Development', 10),
+    ('mobile_development', 'Mobile Development', 20),
+    ('design_&_ui/ux', 'Design & UI/UX', 30)
+ON CONFLICT (slug) DO NOTHING;
+
+-- Any other value already stored on a project becomes a category of its own
+INSERT INTO categories (slug, name, sort_order)
+SELECT DISTINCT category, initcap(replace(category, '_', ' ')), 100
+FROM projects
+ON CONFLICT (slug) DO NOTHING;
+
+ALTER TABLE projects
+    ADD CONSTRAINT fk_projects_category
+    FOREIGN KEY (category) REFERENCES categories(slug)
+    ON UPDATE CASCADE ON DELETE RESTRICT;
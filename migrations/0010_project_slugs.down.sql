@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS project_slug_history;
+ALTER TABLE projects DROP CONSTRAINT IF EXISTS projects_slug_key;
+ALTER TABLE projects DROP COLUMN IF EXISTS slug;
@@ -0,0 +1,17 @@
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS slug VARCHAR(120);
+
+-- Unique placeholders ('_' never appears in a slug); the real slugs of existing projects are
+-- computed with the same `slugify` as new ones by the Rust backfill of this migration
+UPDATE projects SET slug = '_' || id::text WHERE slug IS NULL;
+
+ALTER TABLE projects ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE projects ADD CONSTRAINT projects_slug_key UNIQUE (slug);
+
+-- Previous slugs of renamed projects, kept so old URLs can redirect
+CREATE TABLE IF NOT EXISTS project_slug_history (
+    slug VARCHAR(120) PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_slug_history_project ON project_slug_history(project_id);
@@ -0,0 +1,9 @@
+-- Existing projects were public, so they start out published
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'published'
+    CHECK (status IN ('draft', 'published', 'archived'));
+ALTER TABLE projects ALTER COLUMN status SET DEFAULT 'draft';
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS publish_at TIMESTAMPTZ;
+
+-- Drafts waiting for the scheduled publisher
+CREATE INDEX IF NOT EXISTS idx_projects_scheduled ON projects(publish_at) WHERE status = 'draft' AND publish_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_projects_status ON projects(status);
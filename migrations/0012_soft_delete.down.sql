@@ -0,0 +1,11 @@
+-- Trashed rows would become visible again, so they are removed for good
+DELETE FROM projects WHERE deleted_at IS NOT NULL;
+DELETE FROM employees WHERE deleted_at IS NOT NULL;
+
+DROP INDEX IF EXISTS idx_employees_email_active;
+ALTER TABLE employees DROP CONSTRAINT IF EXISTS employees_email_key;
+ALTER TABLE employees ADD CONSTRAINT employees_email_key UNIQUE (email);
+DROP INDEX IF EXISTS idx_employees_deleted_at;
+DROP INDEX IF EXISTS idx_projects_deleted_at;
+ALTER TABLE employees DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE projects DROP COLUMN IF EXISTS deleted_at;
@@ -0,0 +1,11 @@
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE employees ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- Trash listing and the purge job only look at deleted rows
+CREATE INDEX IF NOT EXISTS idx_projects_deleted_at ON projects(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_employees_deleted_at ON employees(deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- Emails only need to be unique among employees that aren't in the trash, so a trashed
+-- employee doesn't block creating a new one with the same email
+ALTER TABLE employees DROP CONSTRAINT IF EXISTS employees_email_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_employees_email_active ON employees(email) WHERE deleted_at IS NULL;
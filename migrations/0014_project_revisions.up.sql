@@ -0,0 +1,11 @@
+-- Content of a project before each change, numbered per project
+CREATE TABLE IF NOT EXISTS project_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    project_id UUID NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    content JSONB NOT NULL,
+    -- Who made the change that replaced this content
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (project_id, revision)
+);
@@ -1,22 +1,1010 @@
+//! Application configuration, loaded once at startup.
+//!
+//! Values are layered, later layers winning:
+//! 1. built-in defaults (some depend on the profile),
+//! 2. the optional TOML file (`CONFIG_FILE`, or `config.toml` when present): top-level sections
+//!    first, then the `[dev.*]` or `[prod.*]` sections of the active profile,
+//! 3. environment variables (and `.env`).
+//!
+//! Every problem found is collected into one `ConfigError` instead of failing on the first.
+
 use std::env;
-use deadpool_postgres::{Config, Pool, ManagerConfig, RecyclingMethod};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use deadpool_postgres::{Pool, ManagerConfig, RecyclingMethod, Runtime};
+use tokio_postgres_rustls::MakeRustlsConnect;
+use serde::Deserialize;
+use actix_web::http::header::HeaderName;
+use actix_web::http::Method;
+use crate::cors::OriginPattern;
+use crate::db_tls::{self, SslMode};
+use crate::rate_limit::memory::MemoryStore;
+use crate::rate_limit::postgres::PostgresStore;
+use crate::rate_limit::{KeyBy, Limit, RateLimitStore, RouteGroup};
+use crate::services::storage::ImageStorage;
+use crate::services::image_processing::{ImagePipeline, OutputFormat, RenditionSpec};
+use crate::services::storage::cloudinary::{CloudinaryCredentials, CloudinaryStorage};
+use crate::services::storage::local::LocalStorage;
+use crate::services::storage::s3::{S3Config, S3Storage};
+
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Origins allowed by default in the `dev` profile: the usual frontend dev servers.
+/// No origin is allowed by default in `prod`.
+const DEV_ORIGINS: &[&str] = &[
+    "http://localhost:3000",
+    "http://localhost:3001",
+    "http://localhost:3002",
+    "http://127.0.0.1:3000",
+    "http://localhost:5173",
+    "http://127.0.0.1:5173",
+];
+
+const DEFAULT_CORS_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+const DEFAULT_CORS_HEADERS: &[&str] = &["accept", "authorization", "content-type", "user-agent", "x-api-key", "x-requested-with"];
+
+/// Selected with `APP_PROFILE` (default `dev`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    Dev,
+    /// Stricter validation (e.g. of the JWT secret) and no localhost CORS origins by default.
+    Prod,
+}
+
+impl Profile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Profile::Dev => "dev",
+            Profile::Prod => "prod",
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
-pub fn get_pg_pool() -> Pool {
-    // Try DATABASE_URL first (for Railway/production)
-    if let Ok(database_url) = env::var("DATABASE_URL") {
-        let pg_config: tokio_postgres::Config = database_url.parse().expect("Invalid DATABASE_URL");
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dev" | "development" => Ok(Profile::Dev),
+            "prod" | "production" => Ok(Profile::Prod),
+            other => Err(format!("Unknown profile: {}", other)),
+        }
+    }
+}
+
+pub struct AppConfig {
+    pub profile: Profile,
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub auth: AuthConfig,
+    pub cors: CorsConfig,
+    pub storage: StorageConfig,
+    pub uploads: UploadLimits,
+    pub images: ImagePipeline,
+    /// How often scheduled drafts are checked for publishing.
+    pub publish_interval: Duration,
+    pub trash: TrashSettings,
+    pub rate_limit: RateLimitConfig,
+}
+
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+pub struct DatabaseConfig {
+    pub connection: tokio_postgres::Config,
+    pub ssl_mode: SslMode,
+    /// Built once at startup so a bad root certificate is reported with the other problems.
+    pub tls: MakeRustlsConnect,
+    pub max_size: usize,
+    /// How long a request waits for a free connection; `None` waits forever.
+    pub wait_timeout: Option<Duration>,
+    /// How long opening a new connection may take.
+    pub create_timeout: Option<Duration>,
+    /// How long the health check of a returned connection may take.
+    pub recycle_timeout: Option<Duration>,
+    pub recycling: RecyclingMethod,
+    /// Pending migrations are applied on boot.
+    pub auto_migrate: bool,
+}
+
+impl DatabaseConfig {
+    /// Fails like `AppConfig::load`, so main reports both the same way.
+    pub fn create_pool(&self) -> Result<Pool, ConfigError> {
         let mgr_config = ManagerConfig {
-            recycling_method: RecyclingMethod::Fast,
+            recycling_method: self.recycling.clone(),
         };
-        let mgr = deadpool_postgres::Manager::from_config(pg_config, tokio_postgres::NoTls, mgr_config);
-        return Pool::builder(mgr).max_size(16).build().unwrap();
-    }
-    
-    // Fallback to individual variables (for local development)
-    let mut cfg = Config::new();
-    cfg.host = Some(env::var("PG_HOST").expect("PG_HOST not set"));
-    cfg.user = Some(env::var("PG_USER").expect("PG_USER not set"));
-    cfg.password = env::var("PG_PASS").ok();
-    cfg.dbname = Some(env::var("PG_DB").expect("PG_DB not set"));
-    cfg.create_pool(None, tokio_postgres::NoTls).unwrap()
-}
\ No newline at end of file
+        let mgr = deadpool_postgres::Manager::from_config(self.connection.clone(), self.tls.clone(), mgr_config);
+        Pool::builder(mgr)
+            .max_size(self.max_size)
+            .wait_timeout(self.wait_timeout)
+            .create_timeout(self.create_timeout)
+            .recycle_timeout(self.recycle_timeout)
+            .runtime(Runtime::Tokio1)
+            .build()
+            .map_err(|e| ConfigError(vec![format!("database: cannot build the connection pool: {}", e)]))
+    }
+}
+
+pub struct AuthConfig {
+    pub jwt_secret: String,
+    pub token_ttl_secs: i64,
+    /// Credentials for the admin account created on first boot, if configured.
+    pub bootstrap_admin: Option<(String, String)>,
+}
+
+pub struct CorsConfig {
+    pub allowed_origins: Vec<OriginPattern>,
+    pub allowed_methods: Vec<Method>,
+    pub allowed_headers: Vec<HeaderName>,
+    /// Lets browsers send cookies and `Authorization` headers set by the browser itself.
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight response.
+    pub max_age_secs: usize,
+}
+
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// Directory used by local storage and served under `/uploads`.
+    pub upload_dir: String,
+}
+
+pub enum StorageBackend {
+    Local,
+    Cloudinary {
+        cloud_name: String,
+        upload_preset: String,
+        /// API key and secret; only needed to delete assets.
+        credentials: Option<(String, String)>,
+    },
+    S3(S3Config),
+}
+
+impl StorageConfig {
+    pub fn build(&self) -> Result<Arc<dyn ImageStorage>, String> {
+        Ok(match &self.backend {
+            StorageBackend::Local => Arc::new(LocalStorage::new(&self.upload_dir, "/uploads").map_err(|e| e.to_string())?),
+            StorageBackend::Cloudinary { cloud_name, upload_preset, credentials } => Arc::new(CloudinaryStorage::new(
+                cloud_name.clone(),
+                upload_preset.clone(),
+                credentials.clone().map(|(api_key, api_secret)| CloudinaryCredentials { api_key, api_secret }),
+            )),
+            StorageBackend::S3(config) => Arc::new(S3Storage::new(config.clone())),
+        })
+    }
+}
+
+/// Size caps enforced while streaming multipart uploads.
+pub struct UploadLimits {
+    pub max_file_bytes: usize,
+    pub max_request_bytes: usize,
+}
+
+/// How long trashed items are kept (`0` days keeps them until restored) and how often
+/// expired ones are purged.
+#[derive(Debug, Clone, Copy)]
+pub struct TrashSettings {
+    pub retention_days: u32,
+    pub purge_interval: Duration,
+}
+
+/// Limits per route group; a group without a limit isn't limited.
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    pub store: RateLimitStoreKind,
+    /// Take the client address from `X-Forwarded-For` (set by Railway's proxy) instead of the
+    /// TCP peer.
+    pub trust_proxy: bool,
+    pub auth: Option<Limit>,
+    pub upload: Option<Limit>,
+    pub write: Option<Limit>,
+    pub read: Option<Limit>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitStoreKind {
+    Memory,
+    /// Shared by every instance.
+    Postgres,
+}
+
+impl RateLimitConfig {
+    pub fn limit(&self, group: RouteGroup) -> Option<&Limit> {
+        match group {
+            RouteGroup::Auth => self.auth.as_ref(),
+            RouteGroup::Upload => self.upload.as_ref(),
+            RouteGroup::Write => self.write.as_ref(),
+            RouteGroup::Read => self.read.as_ref(),
+        }
+    }
+
+    pub fn build_store(&self, pool: &Pool) -> Arc<dyn RateLimitStore> {
+        match self.store {
+            RateLimitStoreKind::Memory => Arc::new(MemoryStore::default()),
+            RateLimitStoreKind::Postgres => Arc::new(PostgresStore { pool: pool.clone() }),
+        }
+    }
+}
+
+/// Every problem found while loading the configuration.
+#[derive(Debug)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration ({} problems):", self.0.len())?;
+        for problem in &self.0 {
+            write!(f, "\n  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl AppConfig {
+    /// Reads the TOML file (if any) and the environment, and validates the result.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(&|key| env::var(key).ok())
+    }
+
+    /// `load` with the environment variables looked up through `env`.
+    fn load_from(env: Env) -> Result<Self, ConfigError> {
+        let mut errors = Vec::new();
+
+        let profile = match env_value(env, "APP_PROFILE") {
+            Some(value) => value.trim().parse().unwrap_or_else(|_| {
+                errors.push(format!("APP_PROFILE: '{}' is not a profile, expected dev or prod", value));
+                Profile::Dev
+            }),
+            None => Profile::Dev,
+        };
+
+        let mut raw = match read_file(env, profile) {
+            Ok(raw) => raw,
+            Err(problem) => {
+                errors.push(problem);
+                RawConfig::default()
+            }
+        };
+        raw.apply_env(env, &mut errors);
+
+        let config = raw.validate(profile, &mut errors);
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError(errors))
+        }
+    }
+}
+
+/// `CONFIG_FILE` must exist when set; the default `config.toml` is optional.
+fn read_file(env: Env, profile: Profile) -> Result<RawConfig, String> {
+    let (path, required) = match env_value(env, "CONFIG_FILE") {
+        Some(path) => (path, true),
+        None => (DEFAULT_CONFIG_FILE.to_string(), false),
+    };
+    if !required && !Path::new(&path).exists() {
+        return Ok(RawConfig::default());
+    }
+
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+    parse_file(&path, &text, profile)
+}
+
+fn parse_file(path: &str, text: &str, profile: Profile) -> Result<RawConfig, String> {
+    // toml errors span several lines; keep each problem on one
+    let problem = |e: &dyn fmt::Display| format!("{}: {}", path, e.to_string().split_whitespace().collect::<Vec<_>>().join(" "));
+    let mut table: toml::Table = text.parse().map_err(|e: toml::de::Error| problem(&e))?;
+
+    // Only the sections of the active profile apply
+    let dev = table.remove("dev");
+    let prod = table.remove("prod");
+    let overrides = match profile {
+        Profile::Dev => dev,
+        Profile::Prod => prod,
+    };
+    match overrides {
+        Some(toml::Value::Table(overrides)) => merge(&mut table, overrides),
+        Some(_) => return Err(format!("{}: [{}] must be a table", path, profile)),
+        None => {}
+    }
+
+    table.try_into().map_err(|e: toml::de::Error| problem(&e))
+}
+
+fn merge(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => merge(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Looks up an environment variable; the process environment outside tests.
+type Env<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// Unset and blank variables are treated alike.
+fn env_value(env: Env, key: &str) -> Option<String> {
+    env(key).filter(|v| !v.trim().is_empty())
+}
+
+/// Overrides `target` with the parsed variable, recording a problem when it doesn't parse.
+fn env_parse<T: FromStr>(env: Env, errors: &mut Vec<String>, key: &str, expected: &str, target: &mut Option<T>) {
+    if let Some(value) = env_value(env, key) {
+        match value.trim().parse() {
+            Ok(parsed) => *target = Some(parsed),
+            Err(_) => errors.push(format!("{}: expected {}, got '{}'", key, expected, value)),
+        }
+    }
+}
+
+fn env_string(env: Env, key: &str, target: &mut Option<String>) {
+    if let Some(value) = env_value(env, key) {
+        *target = Some(value);
+    }
+}
+
+/// Comma separated values.
+fn env_list(env: Env, key: &str, target: &mut Option<Vec<String>>) {
+    if let Some(value) = env_value(env, key) {
+        *target = Some(split_list(&value));
+    }
+}
+
+fn env_flag(env: Env, errors: &mut Vec<String>, key: &str, target: &mut Option<bool>) {
+    if let Some(value) = env_value(env, key) {
+        match value.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" => *target = Some(true),
+            "false" | "0" | "no" => *target = Some(false),
+            _ => errors.push(format!("{}: expected true or false, got '{}'", key, value)),
+        }
+    }
+}
+
+/// The configuration as read from the file and environment, before defaults and validation.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawConfig {
+    server: RawServer,
+    database: RawDatabase,
+    auth: RawAuth,
+    cors: RawCors,
+    storage: RawStorage,
+    uploads: RawUploads,
+    images: RawImages,
+    jobs: RawJobs,
+    trash: RawTrash,
+    rate_limit: RawRateLimit,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawServer {
+    host: Option<String>,
+    port: Option<u16>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawDatabase {
+    /// Takes precedence over the individual connection settings.
+    url: Option<String>,
+    host: Option<String>,
+    user: Option<String>,
+    password: Option<String>,
+    name: Option<String>,
+    /// Overrides `sslmode` of the URL.
+    ssl_mode: Option<String>,
+    /// PEM bundle of the CAs trusted for the server certificate, instead of the bundled roots.
+    ssl_root_cert: Option<String>,
+    max_size: Option<usize>,
+    wait_timeout_secs: Option<u64>,
+    create_timeout_secs: Option<u64>,
+    recycle_timeout_secs: Option<u64>,
+    recycling: Option<String>,
+    auto_migrate: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawAuth {
+    jwt_secret: Option<String>,
+    token_ttl_minutes: Option<i64>,
+    admin_email: Option<String>,
+    admin_password: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawCors {
+    allowed_origins: Option<Vec<String>>,
+    allowed_methods: Option<Vec<String>>,
+    allowed_headers: Option<Vec<String>>,
+    allow_credentials: Option<bool>,
+    max_age_secs: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawStorage {
+    backend: Option<String>,
+    upload_dir: Option<String>,
+    cloudinary: RawCloudinary,
+    s3: RawS3,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawCloudinary {
+    cloud_name: Option<String>,
+    upload_preset: Option<String>,
+    api_key: Option<String>,
+    api_secret: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawS3 {
+    endpoint: Option<String>,
+    region: Option<String>,
+    bucket: Option<String>,
+    access_key_id: Option<String>,
+    secret_access_key: Option<String>,
+    public_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawUploads {
+    max_file_mb: Option<usize>,
+    max_request_mb: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawImages {
+    /// Comma separated `name:max_width` pairs.
+    renditions: Option<String>,
+    output_format: Option<String>,
+    quality: Option<u8>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawJobs {
+    publish_interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawTrash {
+    retention_days: Option<u32>,
+    purge_interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawRateLimit {
+    enabled: Option<bool>,
+    store: Option<String>,
+    trust_proxy: Option<bool>,
+    auth: RawRateGroup,
+    upload: RawRateGroup,
+    write: RawRateGroup,
+    read: RawRateGroup,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawRateGroup {
+    /// `0` turns the limit off.
+    per_minute: Option<u32>,
+    burst: Option<u32>,
+    key: Option<String>,
+}
+
+impl RawConfig {
+    fn apply_env(&mut self, env: Env, errors: &mut Vec<String>) {
+        env_string(env, "HOST", &mut self.server.host);
+        env_parse(env, errors, "PORT", "a port number", &mut self.server.port);
+
+        env_string(env, "DATABASE_URL", &mut self.database.url);
+        env_string(env, "PG_HOST", &mut self.database.host);
+        env_string(env, "PG_USER", &mut self.database.user);
+        env_string(env, "PG_PASS", &mut self.database.password);
+        env_string(env, "PG_DB", &mut self.database.name);
+        env_string(env, "DB_SSL_MODE", &mut self.database.ssl_mode);
+        env_string(env, "DB_SSL_ROOT_CERT", &mut self.database.ssl_root_cert);
+        env_parse(env, errors, "DB_POOL_MAX_SIZE", "a number", &mut self.database.max_size);
+        env_parse(env, errors, "DB_POOL_WAIT_TIMEOUT_SECS", "a number of seconds", &mut self.database.wait_timeout_secs);
+        env_parse(env, errors, "DB_POOL_CREATE_TIMEOUT_SECS", "a number of seconds", &mut self.database.create_timeout_secs);
+        env_parse(env, errors, "DB_POOL_RECYCLE_TIMEOUT_SECS", "a number of seconds", &mut self.database.recycle_timeout_secs);
+        env_string(env, "DB_POOL_RECYCLING", &mut self.database.recycling);
+        env_flag(env, errors, "AUTO_MIGRATE", &mut self.database.auto_migrate);
+
+        env_string(env, "JWT_SECRET", &mut self.auth.jwt_secret);
+        env_parse(env, errors, "JWT_TTL_MINUTES", "a number of minutes", &mut self.auth.token_ttl_minutes);
+        env_string(env, "ADMIN_EMAIL", &mut self.auth.admin_email);
+        env_string(env, "ADMIN_PASSWORD", &mut self.auth.admin_password);
+
+        env_list(env, "CORS_ALLOWED_ORIGINS", &mut self.cors.allowed_origins);
+        env_list(env, "CORS_ALLOWED_METHODS", &mut self.cors.allowed_methods);
+        env_list(env, "CORS_ALLOWED_HEADERS", &mut self.cors.allowed_headers);
+        env_flag(env, errors, "CORS_ALLOW_CREDENTIALS", &mut self.cors.allow_credentials);
+        env_parse(env, errors, "CORS_MAX_AGE_SECS", "a number of seconds", &mut self.cors.max_age_secs);
+
+        env_string(env, "STORAGE_BACKEND", &mut self.storage.backend);
+        env_string(env, "UPLOAD_DIR", &mut self.storage.upload_dir);
+        let cloudinary = &mut self.storage.cloudinary;
+        env_string(env, "CLOUDINARY_CLOUD_NAME", &mut cloudinary.cloud_name);
+        env_string(env, "CLOUDINARY_UPLOAD_PRESET", &mut cloudinary.upload_preset);
+        env_string(env, "CLOUDINARY_API_KEY", &mut cloudinary.api_key);
+        env_string(env, "CLOUDINARY_API_SECRET", &mut cloudinary.api_secret);
+        let s3 = &mut self.storage.s3;
+        env_string(env, "S3_ENDPOINT", &mut s3.endpoint);
+        env_string(env, "S3_REGION", &mut s3.region);
+        env_string(env, "S3_BUCKET", &mut s3.bucket);
+        env_string(env, "S3_ACCESS_KEY_ID", &mut s3.access_key_id);
+        env_string(env, "S3_SECRET_ACCESS_KEY", &mut s3.secret_access_key);
+        env_string(env, "S3_PUBLIC_URL", &mut s3.public_url);
+
+        env_parse(env, errors, "MAX_UPLOAD_FILE_MB", "a number of megabytes", &mut self.uploads.max_file_mb);
+        env_parse(env, errors, "MAX_UPLOAD_REQUEST_MB", "a number of megabytes", &mut self.uploads.max_request_mb);
+
+        env_string(env, "IMAGE_RENDITIONS", &mut self.images.renditions);
+        env_string(env, "IMAGE_OUTPUT_FORMAT", &mut self.images.output_format);
+        env_parse(env, errors, "IMAGE_QUALITY", "a number from 1 to 100", &mut self.images.quality);
+
+        env_parse(env, errors, "PUBLISH_INTERVAL_SECS", "a number of seconds", &mut self.jobs.publish_interval_secs);
+
+        env_parse(env, errors, "TRASH_RETENTION_DAYS", "a number of days", &mut self.trash.retention_days);
+        env_parse(env, errors, "TRASH_PURGE_INTERVAL_SECS", "a number of seconds", &mut self.trash.purge_interval_secs);
+
+        let rate_limit = &mut self.rate_limit;
+        env_flag(env, errors, "RATE_LIMIT_ENABLED", &mut rate_limit.enabled);
+        env_string(env, "RATE_LIMIT_STORE", &mut rate_limit.store);
+        env_flag(env, errors, "RATE_LIMIT_TRUST_PROXY", &mut rate_limit.trust_proxy);
+        for (prefix, group) in [
+            ("RATE_LIMIT_AUTH", &mut rate_limit.auth),
+            ("RATE_LIMIT_UPLOAD", &mut rate_limit.upload),
+            ("RATE_LIMIT_WRITE", &mut rate_limit.write),
+            ("RATE_LIMIT_READ", &mut rate_limit.read),
+        ] {
+            env_parse(env, errors, &format!("{}_PER_MINUTE", prefix), "a number", &mut group.per_minute);
+            env_parse(env, errors, &format!("{}_BURST", prefix), "a number", &mut group.burst);
+            env_string(env, &format!("{}_KEY", prefix), &mut group.key);
+        }
+    }
+
+    /// Applies the defaults. Problems are pushed to `errors`; the returned value is only
+    /// meaningful when none were found.
+    fn validate(self, profile: Profile, errors: &mut Vec<String>) -> AppConfig {
+        AppConfig {
+            profile,
+            server: ServerConfig {
+                host: self.server.host.unwrap_or_else(|| "0.0.0.0".to_string()),
+                port: self.server.port.unwrap_or(8080),
+            },
+            database: validate_database(self.database, errors),
+            auth: validate_auth(self.auth, profile, errors),
+            cors: validate_cors(self.cors, profile, errors),
+            storage: validate_storage(self.storage, errors),
+            uploads: validate_uploads(self.uploads, errors),
+            images: validate_images(self.images, errors),
+            publish_interval: Duration::from_secs(self.jobs.publish_interval_secs.unwrap_or(60).max(1)),
+            trash: TrashSettings {
+                retention_days: self.trash.retention_days.unwrap_or(30),
+                purge_interval: Duration::from_secs(self.trash.purge_interval_secs.unwrap_or(3600).max(1)),
+            },
+            rate_limit: validate_rate_limit(self.rate_limit, errors),
+        }
+    }
+}
+
+/// `sslmode` and `sslrootcert` are taken out of the URL before tokio-postgres parses it; the
+/// explicit settings win over them.
+fn validate_database(raw: RawDatabase, errors: &mut Vec<String>) -> DatabaseConfig {
+    let mut url_ssl_mode = None;
+    let mut url_root_cert = None;
+    let mut connection = match raw.url {
+        Some(url) => {
+            let (url, ssl_mode, root_cert) = db_tls::split_ssl_options(&url);
+            url_ssl_mode = ssl_mode;
+            url_root_cert = root_cert;
+            url.parse().unwrap_or_else(|e| {
+                errors.push(format!("DATABASE_URL (database.url): invalid connection string: {}", e));
+                tokio_postgres::Config::new()
+            })
+        }
+        None => {
+            let mut connection = tokio_postgres::Config::new();
+            match (raw.host, raw.user, raw.name) {
+                (Some(host), Some(user), Some(name)) => {
+                    connection.host(&host).user(&user).dbname(&name);
+                    if let Some(password) = raw.password {
+                        connection.password(password);
+                    }
+                }
+                _ => errors.push(
+                    "DATABASE_URL (database.url) must be set, or PG_HOST, PG_USER and PG_DB (database.host, database.user, database.name)"
+                        .to_string(),
+                ),
+            }
+            connection
+        }
+    };
+
+    let ssl_mode = match raw.ssl_mode.or(url_ssl_mode) {
+        Some(value) => value.parse().unwrap_or_else(|e| {
+            errors.push(format!("DB_SSL_MODE (database.ssl_mode): {}", e));
+            SslMode::Prefer
+        }),
+        None => SslMode::Prefer,
+    };
+    connection.ssl_mode(ssl_mode.transport());
+    let root_cert = raw.ssl_root_cert.or(url_root_cert).filter(|path| !path.is_empty());
+    let tls = db_tls::connector(ssl_mode, root_cert.as_deref().map(Path::new)).unwrap_or_else(|e| {
+        errors.push(format!("DB_SSL_ROOT_CERT (database.ssl_root_cert): {}", e));
+        db_tls::connector(SslMode::Disable, None).expect("TLS without verification always builds")
+    });
+
+    let max_size = raw.max_size.unwrap_or(16);
+    if max_size == 0 {
+        errors.push("DB_POOL_MAX_SIZE (database.max_size): must be at least 1".to_string());
+    }
+    // 0 turns a timeout off
+    let timeout = |secs: Option<u64>, default: u64| Some(secs.unwrap_or(default)).filter(|secs| *secs > 0).map(Duration::from_secs);
+
+    let recycling = match raw.recycling.as_deref().unwrap_or("fast") {
+        "fast" => RecyclingMethod::Fast,
+        "verified" => RecyclingMethod::Verified,
+        "clean" => RecyclingMethod::Clean,
+        other => {
+            errors.push(format!("DB_POOL_RECYCLING (database.recycling): unknown method '{}', expected fast, verified or clean", other));
+            RecyclingMethod::Fast
+        }
+    };
+
+    DatabaseConfig {
+        connection,
+        ssl_mode,
+        tls,
+        max_size,
+        wait_timeout: timeout(raw.wait_timeout_secs, 30),
+        create_timeout: timeout(raw.create_timeout_secs, 10),
+        recycle_timeout: timeout(raw.recycle_timeout_secs, 5),
+        recycling,
+        auto_migrate: raw.auto_migrate.unwrap_or(true),
+    }
+}
+
+fn validate_auth(raw: RawAuth, profile: Profile, errors: &mut Vec<String>) -> AuthConfig {
+    let jwt_secret = raw.jwt_secret.unwrap_or_default();
+    if jwt_secret.is_empty() {
+        errors.push("JWT_SECRET (auth.jwt_secret) must be set".to_string());
+    } else if profile == Profile::Prod && jwt_secret.len() < 32 {
+        errors.push("JWT_SECRET (auth.jwt_secret): must be at least 32 characters in the prod profile".to_string());
+    }
+
+    let ttl_minutes = raw.token_ttl_minutes.unwrap_or(60);
+    if ttl_minutes < 1 {
+        errors.push("JWT_TTL_MINUTES (auth.token_ttl_minutes): must be at least 1".to_string());
+    }
+
+    let bootstrap_admin = match (raw.admin_email, raw.admin_password) {
+        (Some(email), Some(password)) => Some((email, password)),
+        (None, None) => None,
+        _ => {
+            errors.push("ADMIN_EMAIL and ADMIN_PASSWORD (auth.admin_email, auth.admin_password) must be set together".to_string());
+            None
+        }
+    };
+
+    AuthConfig {
+        jwt_secret,
+        token_ttl_secs: ttl_minutes * 60,
+        bootstrap_admin,
+    }
+}
+
+fn validate_cors(raw: RawCors, profile: Profile, errors: &mut Vec<String>) -> CorsConfig {
+    let origins = raw.allowed_origins.unwrap_or_else(|| match profile {
+        Profile::Dev => DEV_ORIGINS.iter().map(|o| o.to_string()).collect(),
+        Profile::Prod => Vec::new(),
+    });
+    let allowed_origins: Vec<OriginPattern> = origins
+        .iter()
+        .filter_map(|origin| {
+            origin
+                .parse()
+                .map_err(|e| errors.push(format!("CORS_ALLOWED_ORIGINS (cors.allowed_origins): {}", e)))
+                .ok()
+        })
+        .collect();
+
+    let methods = raw.allowed_methods.unwrap_or_else(|| to_strings(DEFAULT_CORS_METHODS));
+    let allowed_methods = methods
+        .iter()
+        .filter_map(|method| {
+            Method::from_bytes(method.trim().to_uppercase().as_bytes())
+                .map_err(|_| errors.push(format!("CORS_ALLOWED_METHODS (cors.allowed_methods): '{}' is not an HTTP method", method)))
+                .ok()
+        })
+        .collect();
+
+    let headers = raw.allowed_headers.unwrap_or_else(|| to_strings(DEFAULT_CORS_HEADERS));
+    let allowed_headers = headers
+        .iter()
+        .filter_map(|header| {
+            HeaderName::from_str(header.trim())
+                .map_err(|_| errors.push(format!("CORS_ALLOWED_HEADERS (cors.allowed_headers): '{}' is not a header name", header)))
+                .ok()
+        })
+        .collect();
+
+    let allow_credentials = raw.allow_credentials.unwrap_or(false);
+    if allow_credentials && allowed_origins.contains(&OriginPattern::Any) {
+        errors.push("CORS_ALLOW_CREDENTIALS (cors.allow_credentials): can't be combined with the `*` origin".to_string());
+    }
+
+    CorsConfig {
+        allowed_origins,
+        allowed_methods,
+        allowed_headers,
+        allow_credentials,
+        max_age_secs: raw.max_age_secs.unwrap_or(3600),
+    }
+}
+
+/// Defaults to Cloudinary when a cloud name is configured and local disk otherwise.
+fn validate_storage(raw: RawStorage, errors: &mut Vec<String>) -> StorageConfig {
+    let backend = raw.backend.unwrap_or_else(|| {
+        if raw.cloudinary.cloud_name.is_some() { "cloudinary".to_string() } else { "local".to_string() }
+    });
+    let mut require = |value: Option<String>, name: &str| {
+        value.unwrap_or_else(|| {
+            errors.push(format!("{} must be set for the {} storage backend", name, backend));
+            String::new()
+        })
+    };
+
+    let selected = match backend.as_str() {
+        "local" => StorageBackend::Local,
+        "cloudinary" => {
+            let cloudinary = raw.cloudinary;
+            StorageBackend::Cloudinary {
+                cloud_name: require(cloudinary.cloud_name, "CLOUDINARY_CLOUD_NAME (storage.cloudinary.cloud_name)"),
+                upload_preset: cloudinary.upload_preset.unwrap_or_else(|| "portfolio_uploads".to_string()),
+                credentials: cloudinary.api_key.zip(cloudinary.api_secret),
+            }
+        }
+        "s3" => {
+            let s3 = raw.s3;
+            StorageBackend::S3(S3Config {
+                endpoint: require(s3.endpoint, "S3_ENDPOINT (storage.s3.endpoint)"),
+                region: s3.region.unwrap_or_else(|| "auto".to_string()),
+                bucket: require(s3.bucket, "S3_BUCKET (storage.s3.bucket)"),
+                access_key_id: require(s3.access_key_id, "S3_ACCESS_KEY_ID (storage.s3.access_key_id)"),
+                secret_access_key: require(s3.secret_access_key, "S3_SECRET_ACCESS_KEY (storage.s3.secret_access_key)"),
+                public_url: require(s3.public_url, "S3_PUBLIC_URL (storage.s3.public_url)"),
+            })
+        }
+        other => {
+            errors.push(format!("STORAGE_BACKEND (storage.backend): unknown backend '{}', expected local, cloudinary or s3", other));
+            StorageBackend::Local
+        }
+    };
+
+    StorageConfig {
+        backend: selected,
+        upload_dir: raw.upload_dir.unwrap_or_else(|| "./uploads".to_string()),
+    }
+}
+
+fn validate_uploads(raw: RawUploads, errors: &mut Vec<String>) -> UploadLimits {
+    let max_file_mb = raw.max_file_mb.unwrap_or(10);
+    let max_request_mb = raw.max_request_mb.unwrap_or(50);
+    if max_file_mb == 0 || max_request_mb == 0 {
+        errors.push("MAX_UPLOAD_FILE_MB and MAX_UPLOAD_REQUEST_MB (uploads.*): must be at least 1".to_string());
+    }
+    UploadLimits {
+        max_file_bytes: max_file_mb * 1024 * 1024,
+        max_request_bytes: max_request_mb * 1024 * 1024,
+    }
+}
+
+fn validate_images(raw: RawImages, errors: &mut Vec<String>) -> ImagePipeline {
+    let renditions = split_list(raw.renditions.as_deref().unwrap_or("thumbnail:320,medium:1024"))
+        .into_iter()
+        .filter_map(|spec| {
+            let parsed = spec
+                .split_once(':')
+                .and_then(|(name, width)| Some((name.trim(), width.trim().parse::<u32>().ok()?)))
+                .filter(|(name, width)| !name.is_empty() && *width > 0);
+            if parsed.is_none() {
+                errors.push(format!("IMAGE_RENDITIONS (images.renditions): invalid entry '{}', expected name:max_width", spec));
+            }
+            parsed.map(|(name, max_width)| RenditionSpec { name: name.to_string(), max_width })
+        })
+        .collect();
+
+    let output = match raw.output_format.as_deref().unwrap_or("original") {
+        "original" => OutputFormat::Original,
+        "webp" => OutputFormat::WebP,
+        "avif" => OutputFormat::Avif,
+        other => {
+            errors.push(format!("IMAGE_OUTPUT_FORMAT (images.output_format): unknown format '{}', expected original, webp or avif", other));
+            OutputFormat::Original
+        }
+    };
+
+    let quality = raw.quality.unwrap_or(82);
+    if !(1..=100).contains(&quality) {
+        errors.push("IMAGE_QUALITY (images.quality): must be between 1 and 100".to_string());
+    }
+
+    ImagePipeline { renditions, output, quality }
+}
+
+/// Logins and uploads are limited by default, reads are not.
+fn validate_rate_limit(raw: RawRateLimit, errors: &mut Vec<String>) -> RateLimitConfig {
+    let store = match raw.store.as_deref().unwrap_or("memory") {
+        "memory" => RateLimitStoreKind::Memory,
+        "postgres" => RateLimitStoreKind::Postgres,
+        other => {
+            errors.push(format!("RATE_LIMIT_STORE (rate_limit.store): unknown store '{}', expected memory or postgres", other));
+            RateLimitStoreKind::Memory
+        }
+    };
+    let enabled = raw.enabled.unwrap_or(true);
+    let mut group = |raw: RawRateGroup, name: RouteGroup, per_minute: u32, burst: u32| {
+        let per_minute = raw.per_minute.unwrap_or(per_minute);
+        let burst = raw.burst.unwrap_or(burst);
+        let key = raw.key.as_deref().unwrap_or("ip").parse().unwrap_or_else(|e| {
+            errors.push(format!("RATE_LIMIT_{}_KEY (rate_limit.{}.key): {}", name.as_str().to_uppercase(), name, e));
+            KeyBy::Ip
+        });
+        if per_minute > 0 && burst == 0 {
+            errors.push(format!("RATE_LIMIT_{}_BURST (rate_limit.{}.burst): must be at least 1", name.as_str().to_uppercase(), name));
+        }
+        (enabled && per_minute > 0 && burst > 0).then_some(Limit { per_minute, burst, key })
+    };
+
+    RateLimitConfig {
+        store,
+        trust_proxy: raw.trust_proxy.unwrap_or(false),
+        auth: group(raw.auth, RouteGroup::Auth, 10, 5),
+        upload: group(raw.upload, RouteGroup::Upload, 6, 3),
+        write: group(raw.write, RouteGroup::Write, 60, 20),
+        read: group(raw.read, RouteGroup::Read, 0, 60),
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    const PROFILES_TOML: &str = r#"
+        [server]
+        host = "127.0.0.1"
+        port = 1000
+
+        [dev.server]
+        port = 2000
+
+        [prod.server]
+        port = 3000
+
+        [prod.auth]
+        token_ttl_minutes = 15
+    "#;
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    /// A config file unique to the calling test, as tests run in parallel.
+    fn config_file(test: &str, text: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("portfolio-config-{}-{}.toml", std::process::id(), test));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn profile_sections_override_top_level_ones() {
+        let dev = parse_file("config.toml", PROFILES_TOML, Profile::Dev).unwrap();
+        assert_eq!((dev.server.host.as_deref(), dev.server.port), (Some("127.0.0.1"), Some(2000)));
+        assert_eq!(dev.auth.token_ttl_minutes, None);
+
+        let prod = parse_file("config.toml", PROFILES_TOML, Profile::Prod).unwrap();
+        assert_eq!((prod.server.host.as_deref(), prod.server.port), (Some("127.0.0.1"), Some(3000)));
+        assert_eq!(prod.auth.token_ttl_minutes, Some(15));
+    }
+
+    #[test]
+    fn environment_overrides_the_file() {
+        let path = config_file("env-precedence", PROFILES_TOML);
+        let path = path.to_str().unwrap();
+        let base = [
+            ("CONFIG_FILE", path),
+            ("DATABASE_URL", "postgres://app@localhost/portfolio"),
+            ("JWT_SECRET", "secret"),
+        ];
+
+        let config = AppConfig::load_from(&env_of(&base)).unwrap();
+        assert_eq!((config.server.host.as_str(), config.server.port), ("127.0.0.1", 2000));
+
+        let config = AppConfig::load_from(&env_of(&[base.as_slice(), &[("PORT", "4000"), ("HOST", "0.0.0.0")]].concat())).unwrap();
+        assert_eq!((config.server.host.as_str(), config.server.port), ("0.0.0.0", 4000));
+
+        // Blank variables don't override anything
+        let config = AppConfig::load_from(&env_of(&[base.as_slice(), &[("PORT", " ")]].concat())).unwrap();
+        assert_eq!(config.server.port, 2000);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        for text in ["[server]\nprot = 1", "[servr]\nport = 1", "[dev.server]\nprot = 1", "debug = true"] {
+            let error = parse_file("config.toml", text, Profile::Dev).unwrap_err();
+            assert!(error.starts_with("config.toml: ") && error.contains("unknown field"), "{}", error);
+        }
+        // Sections of the other profile are dropped before they are checked
+        assert!(parse_file("config.toml", "[prod.server]\nprot = 1", Profile::Dev).is_ok());
+    }
+
+    #[test]
+    fn every_problem_is_reported_at_once() {
+        let error = AppConfig::load_from(&env_of(&[
+            ("CONFIG_FILE", "/nonexistent/config.toml"),
+            ("PORT", "eighty"),
+            ("AUTO_MIGRATE", "maybe"),
+        ]))
+        .err()
+        .unwrap();
+
+        let expected = [
+            "/nonexistent/config.toml: ",
+            "PORT: expected a port number, got 'eighty'",
+            "AUTO_MIGRATE: expected true or false, got 'maybe'",
+            "DATABASE_URL (database.url) must be set",
+            "JWT_SECRET (auth.jwt_secret) must be set",
+        ];
+        assert_eq!(error.0.len(), expected.len(), "{}", error);
+        for (problem, expected) in error.0.iter().zip(expected) {
+            assert!(problem.starts_with(expected), "{}", problem);
+        }
+        assert!(error.to_string().starts_with("Invalid configuration (5 problems):\n  - /nonexistent/config.toml: "));
+    }
+}
@@ -0,0 +1,234 @@
+//! CORS policy built from `AppConfig.cors`.
+
+use actix_cors::Cors;
+use std::fmt;
+use std::str::FromStr;
+use crate::config::CorsConfig;
+
+/// An entry of `CORS_ALLOWED_ORIGINS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginPattern {
+    /// `https://portfolio.example.com` or `http://localhost:5173`, normalised to lowercase
+    /// without a default port.
+    Exact(String),
+    /// `https://*.example.com`: any subdomain (at any depth) of `example.com` over the same
+    /// scheme and port, but not `example.com` itself.
+    Subdomain {
+        scheme: String,
+        /// Starts with a dot, e.g. `.example.com`.
+        suffix: String,
+        port: Option<u16>,
+    },
+    /// `*`: every origin. Can't be combined with credentials.
+    Any,
+}
+
+impl OriginPattern {
+    pub fn matches(&self, origin: &str) -> bool {
+        let Some(origin) = Origin::parse(origin) else {
+            return false;
+        };
+        match self {
+            OriginPattern::Any => true,
+            OriginPattern::Exact(allowed) => origin.to_string() == *allowed,
+            OriginPattern::Subdomain { scheme, suffix, port } => {
+                origin.scheme == *scheme
+                    && origin.port == *port
+                    && origin
+                        .host
+                        .strip_suffix(suffix.as_str())
+                        .is_some_and(is_hostname)
+            }
+        }
+    }
+}
+
+impl FromStr for OriginPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let pattern = s.trim();
+        if pattern == "*" {
+            return Ok(OriginPattern::Any);
+        }
+        let invalid = || format!("'{}' is not an origin such as https://app.example.com or https://*.example.com", pattern);
+
+        // The wildcard is replaced by a valid label so the rest goes through the same parsing
+        let wildcard = pattern.contains('*');
+        let candidate = if wildcard { pattern.replacen("://*.", "://wildcard.", 1) } else { pattern.to_string() };
+        if candidate.contains('*') {
+            return Err(invalid());
+        }
+        let origin = Origin::parse(&candidate).ok_or_else(invalid)?;
+        if !wildcard {
+            return Ok(OriginPattern::Exact(origin.to_string()));
+        }
+
+        let suffix = origin.host.strip_prefix("wildcard").ok_or_else(invalid)?;
+        // `*.com` would allow every site under a public suffix
+        if !suffix[1..].contains('.') {
+            return Err(format!("'{}' is too broad; use at least two labels after `*.`", pattern));
+        }
+        Ok(OriginPattern::Subdomain {
+            scheme: origin.scheme,
+            suffix: suffix.to_string(),
+            port: origin.port,
+        })
+    }
+}
+
+impl fmt::Display for OriginPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OriginPattern::Exact(origin) => f.write_str(origin),
+            OriginPattern::Subdomain { scheme, suffix, port: Some(port) } => write!(f, "{}://*{}:{}", scheme, suffix, port),
+            OriginPattern::Subdomain { scheme, suffix, port: None } => write!(f, "{}://*{}", scheme, suffix),
+            OriginPattern::Any => f.write_str("*"),
+        }
+    }
+}
+
+/// A parsed `Origin` header: scheme, lowercase host and explicit port.
+#[derive(Debug, PartialEq, Eq)]
+struct Origin {
+    scheme: String,
+    host: String,
+    port: Option<u16>,
+}
+
+impl Origin {
+    /// `None` for `null`, paths, credentials or anything else that isn't `scheme://host[:port]`.
+    fn parse(value: &str) -> Option<Self> {
+        let (scheme, authority) = value.split_once("://")?;
+        let scheme = scheme.to_ascii_lowercase();
+        if scheme != "http" && scheme != "https" {
+            return None;
+        }
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port.parse::<u16>().ok()?)),
+            None => (authority, None),
+        };
+        let host = host.to_ascii_lowercase();
+        if !is_hostname(&host) {
+            return None;
+        }
+        // The default port is the same origin as no port at all
+        let port = port.filter(|port| !matches!((scheme.as_str(), port), ("http", 80) | ("https", 443)));
+        Some(Self { scheme, host, port })
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.host)?;
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        Ok(())
+    }
+}
+
+/// Dot separated labels of letters, digits and inner hyphens (IPv4 addresses included).
+fn is_hostname(host: &str) -> bool {
+    !host.is_empty()
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+pub fn middleware(config: &CorsConfig) -> Cors {
+    let origins = config.allowed_origins.clone();
+    let mut cors = Cors::default()
+        .allowed_origin_fn(move |origin, _req_head| {
+            origin
+                .to_str()
+                .is_ok_and(|origin| origins.iter().any(|pattern| pattern.matches(origin)))
+        })
+        .allowed_methods(config.allowed_methods.clone())
+        .allowed_headers(config.allowed_headers.clone())
+        // Lets clients read when to retry a `429`
+        .expose_headers([actix_web::http::header::RETRY_AFTER])
+        .max_age(config.max_age_secs);
+    if config.allow_credentials {
+        cors = cors.supports_credentials();
+    }
+    cors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(s: &str) -> OriginPattern {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn exact_origin_matches_only_itself() {
+        let allowed = pattern("https://portfolio.example.com");
+        assert!(allowed.matches("https://portfolio.example.com"));
+        assert!(allowed.matches("HTTPS://Portfolio.Example.com"));
+        assert!(allowed.matches("https://portfolio.example.com:443"));
+        assert!(!allowed.matches("http://portfolio.example.com"));
+        assert!(!allowed.matches("https://portfolio.example.com:8443"));
+        assert!(!allowed.matches("https://portfolio.example.com.evil.io"));
+        assert!(!allowed.matches("https://evilportfolio.example.com"));
+    }
+
+    #[test]
+    fn exact_origin_with_port() {
+        let allowed = pattern("http://localhost:5173");
+        assert!(allowed.matches("http://localhost:5173"));
+        assert!(!allowed.matches("http://localhost:3000"));
+        assert!(!allowed.matches("http://localhost"));
+    }
+
+    #[test]
+    fn wildcard_matches_subdomains_only() {
+        let allowed = pattern("https://*.vercel.app");
+        assert!(allowed.matches("https://my-app.vercel.app"));
+        assert!(allowed.matches("https://my-app-git-main-team.vercel.app"));
+        assert!(allowed.matches("https://a.b.vercel.app"));
+        assert!(!allowed.matches("https://vercel.app"));
+        assert!(!allowed.matches("https://evilvercel.app"));
+        assert!(!allowed.matches("https://my-app.vercel.app.evil.io"));
+        assert!(!allowed.matches("http://my-app.vercel.app"));
+        assert!(!allowed.matches("https://my-app.vercel.app:8443"));
+        assert!(!allowed.matches("https://.vercel.app"));
+        assert!(!allowed.matches("https://-bad.vercel.app"));
+    }
+
+    #[test]
+    fn wildcard_with_port() {
+        let allowed = pattern("http://*.localtest.me:3000");
+        assert!(allowed.matches("http://app.localtest.me:3000"));
+        assert!(!allowed.matches("http://app.localtest.me"));
+    }
+
+    #[test]
+    fn malformed_origins_never_match() {
+        let any = pattern("*");
+        assert!(any.matches("https://anything.example"));
+        for origin in ["null", "", "file://", "https://", "https://exa mple.com", "https://example.com/path", "https://user@example.com", "ftp://example.com"] {
+            assert!(!any.matches(origin), "{} should not match", origin);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        for invalid in ["example.com", "https://*.com", "https://*", "https://app.*.example.com", "https://*example.com", "https://example.com/", "ftp://example.com"] {
+            assert!(invalid.parse::<OriginPattern>().is_err(), "{} should be rejected", invalid);
+        }
+    }
+
+    #[test]
+    fn patterns_are_normalised() {
+        assert_eq!(pattern("HTTPS://App.Example.com:443").to_string(), "https://app.example.com");
+        assert_eq!(pattern(" https://*.Example.com ").to_string(), "https://*.example.com");
+        assert_eq!(pattern("http://*.example.com:8080").to_string(), "http://*.example.com:8080");
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use crate::validation::not_blank;
+use crate::models::api_key::{ApiKey, Scope};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateApiKeyDTO {
+    /// What the key is for, e.g. `ci-deploy`.
+    #[validate(length(max = 100), custom(function = "not_blank"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "must contain at least one scope"))]
+    pub scopes: Vec<Scope>,
+    /// The key stops working after this moment; never expires when left out.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Returned once by `POST /api/auth/api-keys`; the key can't be retrieved again.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedApiKeyDTO {
+    #[schema(example = "pk_3f9a1c2e5b7d90a4c6e8f01b2d4a6c8e0f1a3b5c7d9e0f21")]
+    pub key: String,
+    pub api_key: ApiKey,
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use crate::models::audit::{AuditAction, AuditEntity};
+use utoipa::IntoParams;
+
+/// Query string accepted by `GET /api/audit`. Every filter is optional.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    pub entity: Option<AuditEntity>,
+    /// Entity id; most useful together with `entity`.
+    pub id: Option<Uuid>,
+    /// User id of the actor.
+    pub actor: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    /// Inclusive lower bound on the timestamp.
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on the timestamp.
+    pub to: Option<DateTime<Utc>>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// Rows to skip, e.g. `meta.next_offset` of the previous page; overrides `page`.
+    pub offset: Option<i64>,
+}
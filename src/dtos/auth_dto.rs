@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use crate::validation::not_blank;
+use crate::models::user::{Role, User};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct LoginDTO {
+    #[validate(custom(function = "not_blank"))]
+    pub email: String,
+    #[validate(custom(function = "not_blank"))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateUserDTO {
+    #[validate(length(max = 255), email)]
+    pub email: String,
+    #[validate(length(min = 8, max = 128))]
+    pub password: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenDTO {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub user: User,
+}
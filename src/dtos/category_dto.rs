@@ -0,0 +1,39 @@
+use serde::Deserialize;
+use validator::Validate;
+use crate::dtos::project_dto::nullable;
+use crate::validation::not_blank;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateCategoryDTO {
+    #[validate(length(max = 100), custom(function = "crate::validation::slug"))]
+    pub slug: String,
+    #[validate(length(max = 255), custom(function = "not_blank"))]
+    pub name: String,
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+    #[validate(length(max = 255))]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub sort_order: i32,
+}
+
+/// Renaming the slug also updates every project in the category.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateCategoryDTO {
+    #[validate(length(max = 100), custom(function = "crate::validation::slug"))]
+    pub slug: Option<String>,
+    #[validate(length(max = 255), custom(function = "not_blank"))]
+    pub name: Option<String>,
+    /// `null` clears the description.
+    #[validate(length(max = 2000))]
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>)]
+    pub description: Option<Option<String>>,
+    /// `null` clears the icon.
+    #[validate(length(max = 255))]
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<String>)]
+    pub icon: Option<Option<String>>,
+    pub sort_order: Option<i32>,
+}
@@ -0,0 +1,20 @@
+use serde::Deserialize;
+use crate::errors::AppError;
+use crate::models::display::DisplayFormat;
+use utoipa::IntoParams;
+
+/// `?tz=Asia/Jakarta&locale=id_ID` on single-item endpoints; list queries carry the same fields.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DisplayQuery {
+    /// IANA time zone name.
+    pub tz: Option<String>,
+    /// POSIX locale name such as `id_ID` or `en_US`.
+    pub locale: Option<String>,
+}
+
+impl DisplayQuery {
+    pub fn display(&self) -> Result<Option<DisplayFormat>, AppError> {
+        DisplayFormat::from_query(self.tz.as_deref(), self.locale.as_deref())
+    }
+}
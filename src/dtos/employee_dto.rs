@@ -1,15 +1,64 @@
-use serde::{Deserialize};
+use serde::Deserialize;
+use validator::Validate;
+use crate::validation::not_blank;
+use crate::dtos::pagination_dto::SortOrder;
+use crate::errors::AppError;
+use crate::models::display::DisplayFormat;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateEmployeeDTO {
+    #[validate(length(max = 255), custom(function = "not_blank"))]
     pub name: String,
+    #[validate(length(max = 255), custom(function = "not_blank"))]
     pub position: String,
+    #[validate(length(max = 255), email)]
     pub email: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateEmployeeDTO {
+    #[validate(length(max = 255), custom(function = "not_blank"))]
     pub name: Option<String>,
+    #[validate(length(max = 255), custom(function = "not_blank"))]
     pub position: Option<String>,
+    #[validate(length(max = 255), email)]
     pub email: Option<String>,
 }
+
+#[derive(Debug, Deserialize, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmployeeSortField {
+    Name,
+    Position,
+    Email,
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// Query string accepted by `GET /api/employees`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EmployeeListQuery {
+    /// Full-text search over name and position.
+    pub q: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// Rows to skip, e.g. `meta.next_offset` of the previous page; overrides `page`.
+    pub offset: Option<i64>,
+    pub sort: Option<EmployeeSortField>,
+    pub order: Option<SortOrder>,
+    /// See `DisplayQuery`.
+    pub tz: Option<String>,
+    pub locale: Option<String>,
+}
+
+impl EmployeeListQuery {
+    pub fn search_term(&self) -> Option<&str> {
+        self.q.as_deref().map(str::trim).filter(|q| !q.is_empty())
+    }
+
+    pub fn display(&self) -> Result<Option<DisplayFormat>, AppError> {
+        DisplayFormat::from_query(self.tz.as_deref(), self.locale.as_deref())
+    }
+}
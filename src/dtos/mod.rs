@@ -1,2 +1,8 @@
+pub mod api_key_dto;
+pub mod audit_dto;
+pub mod auth_dto;
+pub mod category_dto;
+pub mod display_dto;
 pub mod employee_dto;
-pub mod project_dto;
\ No newline at end of file
+pub mod pagination_dto;
+pub mod project_dto;
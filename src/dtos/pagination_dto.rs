@@ -0,0 +1,102 @@
+use serde::Deserialize;
+use crate::errors::AppError;
+use utoipa::ToSchema;
+
+pub const DEFAULT_PER_PAGE: i64 = 20;
+pub const MAX_PER_PAGE: i64 = 100;
+/// Deeper pages get slow with `OFFSET`; narrow the query instead.
+pub const MAX_PAGE: i64 = 10_000;
+pub const MAX_OFFSET: i64 = MAX_PAGE * MAX_PER_PAGE;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
+
+/// Resolved `page`/`per_page`/`offset` query parameters.
+///
+/// `offset` skips that many rows and takes precedence over `page` when both are given;
+/// `meta.next_offset` of the previous page is the value to pass for infinite scroll.
+#[derive(Debug, Clone, Copy)]
+pub struct PageRequest {
+    pub offset: i64,
+    pub limit: i64,
+}
+
+impl PageRequest {
+    pub fn new(page: Option<i64>, per_page: Option<i64>, offset: Option<i64>) -> Result<Self, AppError> {
+        let limit = per_page.unwrap_or(DEFAULT_PER_PAGE);
+        if !(1..=MAX_PER_PAGE).contains(&limit) {
+            return Err(AppError::BadRequest(format!("per_page must be between 1 and {}", MAX_PER_PAGE)));
+        }
+
+        let offset = match (offset, page) {
+            (Some(offset), _) if !(0..=MAX_OFFSET).contains(&offset) => {
+                return Err(AppError::BadRequest(format!("offset must be between 0 and {}", MAX_OFFSET)));
+            }
+            (Some(offset), _) => offset,
+            (None, Some(page)) if !(1..=MAX_PAGE).contains(&page) => {
+                return Err(AppError::BadRequest(format!("page must be between 1 and {}", MAX_PAGE)));
+            }
+            (None, Some(page)) => (page - 1)
+                .checked_mul(limit)
+                .ok_or_else(|| AppError::BadRequest("page is too large".to_string()))?,
+            (None, None) => 0,
+        };
+
+        Ok(Self { offset, limit })
+    }
+
+    pub fn page(&self) -> i64 {
+        self.offset / self.limit + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_becomes_an_offset() {
+        let request = PageRequest::new(Some(3), Some(25), None).unwrap();
+        assert_eq!((request.offset, request.limit, request.page()), (50, 25, 3));
+    }
+
+    #[test]
+    fn offset_takes_precedence_over_page() {
+        let request = PageRequest::new(Some(3), Some(10), Some(15)).unwrap();
+        assert_eq!((request.offset, request.page()), (15, 2));
+    }
+
+    #[test]
+    fn out_of_range_values_are_rejected() {
+        for (page, per_page, offset) in [
+            (Some(0), None, None),
+            (Some(MAX_PAGE + 1), None, None),
+            (Some(i64::MAX), Some(MAX_PER_PAGE), None),
+            (None, Some(0), None),
+            (None, Some(MAX_PER_PAGE + 1), None),
+            (None, None, Some(-1)),
+            (None, None, Some(MAX_OFFSET + 1)),
+        ] {
+            assert!(
+                matches!(PageRequest::new(page, per_page, offset), Err(AppError::BadRequest(_))),
+                "{:?} {:?} {:?}",
+                page,
+                per_page,
+                offset
+            );
+        }
+    }
+}
@@ -1,17 +1,194 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+use crate::slug::slugify;
+use crate::validation::{image_url, not_blank, tag_names};
+use crate::dtos::pagination_dto::SortOrder;
+use crate::errors::AppError;
+use crate::models::display::DisplayFormat;
+use crate::models::project::{ProjectStatus, Rendition, RenditionSet};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
 pub struct CreateProjectDTO {
+    #[validate(length(max = 255), custom(function = "not_blank"))]
     pub name: String,
+    #[validate(length(max = 10000), custom(function = "not_blank"))]
     pub description: String,
-    pub images: Vec<String>,
+    /// Image objects as returned by `POST /api/projects/upload`, or plain URLs.
+    #[serde(deserialize_with = "image_list")]
+    #[validate(length(min = 1, max = 50, message = "must contain between 1 and 50 images"), nested)]
+    pub images: Vec<NewProjectImageDTO>,
+    #[validate(length(max = 100), custom(function = "not_blank"))]
     pub category: String,
+    /// Tag display names; unknown tags are created on the fly.
+    #[serde(default)]
+    #[validate(length(max = 30, message = "must contain at most 30 tags"), custom(function = "tag_names"))]
+    pub tags: Vec<String>,
+    /// New projects are drafts unless stated otherwise.
+    #[serde(default)]
+    pub status: Option<ProjectStatus>,
+    /// Publishes the draft automatically at this time.
+    #[serde(default)]
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Images are managed through the `/api/projects/{id}/images` endpoints instead.
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
 pub struct UpdateProjectDTO {
+    #[validate(length(max = 255), custom(function = "not_blank"))]
     pub name: Option<String>,
+    #[validate(length(max = 10000), custom(function = "not_blank"))]
     pub description: Option<String>,
-    pub images: Option<Vec<String>>,
+    #[validate(length(max = 100), custom(function = "not_blank"))]
     pub category: Option<String>,
+    /// Replaces the whole tag list when given.
+    #[validate(length(max = 30, message = "must contain at most 30 tags"), custom(function = "tag_names"))]
+    pub tags: Option<Vec<String>>,
+    pub status: Option<ProjectStatus>,
+    /// `null` cancels a scheduled publication.
+    #[serde(default, deserialize_with = "nullable")]
+    #[schema(value_type = Option<DateTime<Utc>>)]
+    pub publish_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// Tells an explicit `null` (`Some(None)`) apart from a missing field (`None`).
+pub fn nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Validate, ToSchema)]
+pub struct NewProjectImageDTO {
+    #[validate(length(max = 2048), custom(function = "image_url"))]
+    pub url: String,
+    #[validate(length(max = 500))]
+    pub alt: Option<String>,
+    #[validate(length(max = 1000))]
+    pub caption: Option<String>,
+    /// Without any cover in the list, the first image becomes the cover.
+    #[serde(default)]
+    pub is_cover: bool,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    #[validate(length(max = 100))]
+    pub blurhash: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = BTreeMap<String, Rendition>)]
+    pub renditions: RenditionSet,
+}
+
+/// Fields left out are unchanged; an empty string clears `alt` or `caption`.
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct UpdateProjectImageDTO {
+    #[validate(length(max = 500))]
+    pub alt: Option<String>,
+    #[validate(length(max = 1000))]
+    pub caption: Option<String>,
+    /// Only `true` is accepted: the previous cover is unset automatically.
+    pub is_cover: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+pub struct ReorderImagesDTO {
+    /// Every image id of the project, in the new order.
+    #[validate(length(min = 1, message = "must list the project's images"))]
+    pub image_ids: Vec<Uuid>,
+}
+
+/// Query string of `GET /api/projects/{id}/revisions/{revision}/diff`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RevisionDiffQuery {
+    /// Revision to compare with; the current content when left out.
+    pub against: Option<i32>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ImageInput {
+    Url(String),
+    Image(NewProjectImageDTO),
+}
+
+fn image_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<NewProjectImageDTO>, D::Error> {
+    let inputs = Vec::<ImageInput>::deserialize(deserializer)?;
+    Ok(inputs
+        .into_iter()
+        .map(|input| match input {
+            ImageInput::Url(url) => NewProjectImageDTO { url, ..Default::default() },
+            ImageInput::Image(image) => image,
+        })
+        .collect())
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectSortField {
+    CreatedAt,
+    UpdatedAt,
+    Name,
+    Category,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TagMatch {
+    /// Projects having every requested tag.
+    All,
+    /// Projects having at least one requested tag.
+    Any,
+}
+
+/// Query string accepted by `GET /api/projects`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProjectListQuery {
+    /// Full-text search over name, description and category.
+    pub q: Option<String>,
+    pub category: Option<String>,
+    /// Comma separated tag slugs or names, e.g. `rust,actix`.
+    pub tags: Option<String>,
+    /// How `tags` combine; defaults to `all`.
+    #[serde(rename = "match")]
+    pub tag_match: Option<TagMatch>,
+    /// Only honoured for users allowed to edit projects; everyone else sees published projects.
+    pub status: Option<ProjectStatus>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// Rows to skip, e.g. `meta.next_offset` of the previous page; overrides `page`.
+    pub offset: Option<i64>,
+    pub sort: Option<ProjectSortField>,
+    pub order: Option<SortOrder>,
+    /// See `DisplayQuery`.
+    pub tz: Option<String>,
+    pub locale: Option<String>,
+}
+
+impl ProjectListQuery {
+    pub fn search_term(&self) -> Option<&str> {
+        self.q.as_deref().map(str::trim).filter(|q| !q.is_empty())
+    }
+
+    pub fn display(&self) -> Result<Option<DisplayFormat>, AppError> {
+        DisplayFormat::from_query(self.tz.as_deref(), self.locale.as_deref())
+    }
+
+    /// Deduplicated tag slugs, `None` when no tag filter was given.
+    pub fn tag_slugs(&self) -> Option<Vec<String>> {
+        let mut slugs: Vec<String> = self
+            .tags
+            .as_deref()?
+            .split(',')
+            .map(slugify)
+            .filter(|slug| !slug.is_empty())
+            .collect();
+        slugs.sort();
+        slugs.dedup();
+        (!slugs.is_empty()).then_some(slugs)
+    }
 }
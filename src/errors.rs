@@ -0,0 +1,154 @@
+use actix_web::{http::header, http::StatusCode, HttpResponse, ResponseError};
+use deadpool_postgres::PoolError;
+use std::fmt;
+use tokio_postgres::error::SqlState;
+use crate::models::api_response::ApiResponse;
+use crate::validation::{field_errors, FieldErrors};
+
+/// Error type shared by repositories, services and handlers.
+///
+/// Every variant maps to an HTTP status and a stable `code` that clients can match on.
+#[derive(Debug)]
+pub enum AppError {
+    NotFound(String),
+    BadRequest(String),
+    Validation(String),
+    /// Request body failed DTO validation; reported per field.
+    InvalidFields(FieldErrors),
+    Conflict(String),
+    Unauthorized(String),
+    Forbidden(String),
+    PayloadTooLarge(String),
+    UnsupportedMediaType(String),
+    /// The client's rate limit bucket is empty; sent with `Retry-After`.
+    RateLimited { retry_after_secs: u64 },
+    /// The database answered with an error.
+    Storage(String),
+    /// No database connection could be obtained from the pool.
+    Unavailable(String),
+    /// A third-party service (e.g. Cloudinary) failed.
+    Upstream(String),
+    Internal(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "not_found",
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Validation(_) | AppError::InvalidFields(_) => "validation_error",
+            AppError::Conflict(_) => "conflict",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::PayloadTooLarge(_) => "payload_too_large",
+            AppError::UnsupportedMediaType(_) => "unsupported_media_type",
+            AppError::RateLimited { .. } => "rate_limited",
+            AppError::Storage(_) => "storage_error",
+            AppError::Unavailable(_) => "storage_unavailable",
+            AppError::Upstream(_) => "upstream_error",
+            AppError::Internal(_) => "internal_error",
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            AppError::NotFound(m)
+            | AppError::BadRequest(m)
+            | AppError::Validation(m)
+            | AppError::Conflict(m)
+            | AppError::Unauthorized(m)
+            | AppError::Forbidden(m)
+            | AppError::PayloadTooLarge(m)
+            | AppError::UnsupportedMediaType(m)
+            | AppError::Storage(m)
+            | AppError::Unavailable(m)
+            | AppError::Upstream(m)
+            | AppError::Internal(m) => m,
+            AppError::InvalidFields(_) => "Validation failed",
+            AppError::RateLimited { .. } => "Too many requests, try again later",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Validation(_) | AppError::InvalidFields(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            AppError::Storage(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::Upstream(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut body = ApiResponse::<()>::error(self.code(), self.to_string());
+        if let AppError::InvalidFields(fields) = self {
+            body.errors = Some(fields.clone());
+        }
+        let mut response = HttpResponse::build(self.status_code());
+        if let AppError::RateLimited { retry_after_secs } = self {
+            response.insert_header((header::RETRY_AFTER, retry_after_secs.to_string()));
+        }
+        response.json(body)
+    }
+}
+
+/// Database messages name tables, constraints and the offending values, so they are only
+/// logged; clients get a fixed message per kind of failure.
+impl From<tokio_postgres::Error> for AppError {
+    fn from(err: tokio_postgres::Error) -> Self {
+        let Some(db_err) = err.as_db_error() else {
+            eprintln!("⚠️  Database error: {}", err);
+            return AppError::Storage("Internal server error".to_string());
+        };
+        eprintln!(
+            "⚠️  Database error {}: {}{}",
+            db_err.code().code(),
+            db_err.message(),
+            db_err.detail().map(|detail| format!(" ({})", detail)).unwrap_or_default()
+        );
+        match *db_err.code() {
+            SqlState::UNIQUE_VIOLATION => AppError::Conflict("Resource already exists".to_string()),
+            SqlState::FOREIGN_KEY_VIOLATION => AppError::Validation("Invalid reference to a related resource".to_string()),
+            SqlState::CHECK_VIOLATION | SqlState::NOT_NULL_VIOLATION => {
+                AppError::Validation("Invalid or missing value".to_string())
+            }
+            _ => AppError::Storage("Internal server error".to_string()),
+        }
+    }
+}
+
+impl From<validator::ValidationErrors> for AppError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        AppError::InvalidFields(field_errors(&errors))
+    }
+}
+
+impl From<PoolError> for AppError {
+    fn from(err: PoolError) -> Self {
+        eprintln!("⚠️  Database pool error: {}", err);
+        AppError::Unavailable("Database unavailable".to_string())
+    }
+}
+
+impl From<actix_web::error::BlockingError> for AppError {
+    fn from(err: actix_web::error::BlockingError) -> Self {
+        AppError::Internal(err.to_string())
+    }
+}
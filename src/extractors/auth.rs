@@ -0,0 +1,90 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpMessage, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+use crate::errors::AppError;
+use crate::models::api_key::{ApiKey, Scope};
+use crate::models::user::{Permission, Role};
+use crate::services::api_key_service::ApiKeyService;
+use crate::services::auth_service::AuthService;
+
+/// The caller identified by a valid `Authorization: Bearer <token>` header, or by an
+/// `X-Api-Key` header acting on behalf of the user who created the key.
+#[derive(Debug, Clone)]
+pub struct AuthUser {
+    pub id: Uuid,
+    pub role: Role,
+    /// Scopes of the API key used, `None` for a login token.
+    pub scopes: Option<Vec<Scope>>,
+}
+
+/// An `X-Api-Key` already accepted earlier in the request, by the rate limiter.
+#[derive(Debug, Clone)]
+pub struct VerifiedApiKey {
+    pub api_key: ApiKey,
+    pub role: Role,
+}
+
+impl AuthUser {
+    /// Both the role and, for API keys, the scopes must allow `permission`.
+    pub fn can(&self, permission: Permission) -> bool {
+        self.role.grants(permission)
+            && self
+                .scopes
+                .as_ref()
+                .is_none_or(|scopes| scopes.iter().any(|scope| scope.permission() == permission))
+    }
+
+    pub fn require(&self, permission: Permission) -> Result<(), AppError> {
+        if self.can(permission) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden("You do not have permission to perform this action".to_string()))
+        }
+    }
+}
+
+impl FromRequest for AuthUser {
+    type Error = AppError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        Box::pin(async move { authenticate(&req).await })
+    }
+}
+
+/// A bearer token wins when both headers are sent.
+async fn authenticate(req: &HttpRequest) -> Result<AuthUser, AppError> {
+    let header = |name: &str| req.headers().get(name).and_then(|value| value.to_str().ok()).map(str::trim);
+
+    if let Some(token) = header("authorization").and_then(|value| value.strip_prefix("Bearer ")) {
+        let auth = req
+            .app_data::<web::Data<AuthService>>()
+            .expect("AuthService is not registered as app data");
+        let claims = auth.verify_token(token.trim())?;
+        return Ok(AuthUser {
+            id: claims.sub,
+            role: claims.role,
+            scopes: None,
+        });
+    }
+
+    let key = header("x-api-key")
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| AppError::Unauthorized("Missing bearer token or API key".to_string()))?;
+    let verified = req.extensions().get::<VerifiedApiKey>().cloned();
+    let (api_key, role) = match verified {
+        Some(VerifiedApiKey { api_key, role }) => (api_key, role),
+        None => {
+            let api_keys = req
+                .app_data::<web::Data<ApiKeyService>>()
+                .expect("ApiKeyService is not registered as app data");
+            api_keys.authenticate(key).await?
+        }
+    };
+    Ok(AuthUser {
+        id: api_key.user_id,
+        role,
+        scopes: Some(api_key.scopes),
+    })
+}
@@ -0,0 +1,82 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use uuid::Uuid;
+use crate::services::api_key_service::ApiKeyService;
+use crate::dtos::api_key_dto::{CreateApiKeyDTO, CreatedApiKeyDTO};
+use crate::errors::AppError;
+use crate::models::api_key::ApiKey;
+use crate::models::api_response::ApiResponse;
+use crate::openapi::ErrorResponse;
+use crate::extractors::auth::AuthUser;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::models::user::Permission;
+
+/// Creates a key acting on behalf of the caller. The key is only ever returned here.
+#[utoipa::path(
+    tag = "auth",
+    request_body = CreateApiKeyDTO,
+    responses(
+        (status = 200, description = "API key created", body = ApiResponse<CreatedApiKeyDTO>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/api/auth/api-keys")]
+pub async fn create_api_key(
+    svc: web::Data<ApiKeyService>,
+    user: AuthUser,
+    body: ValidatedJson<CreateApiKeyDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::UsersManage)?;
+
+    let (api_key, key) = svc.create(user.id, body.0).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        "API key created; store it now, it won't be shown again",
+        Some(CreatedApiKeyDTO { key, api_key }),
+    )))
+}
+
+/// Every key, revoked ones included, newest first.
+#[utoipa::path(
+    tag = "auth",
+    responses(
+        (status = 200, description = "Every API key", body = ApiResponse<Vec<ApiKey>>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/api/auth/api-keys")]
+pub async fn get_api_keys(
+    svc: web::Data<ApiKeyService>,
+    user: AuthUser
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::UsersManage)?;
+
+    let keys = svc.list().await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(format!("{} API keys found", keys.len()), Some(keys))))
+}
+
+/// Revoked keys stop working immediately and stay listed.
+#[utoipa::path(
+    tag = "auth",
+    responses(
+        (status = 200, description = "API key revoked", body = ApiResponse<ApiKey>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "No active key with this id", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[delete("/api/auth/api-keys/{id}")]
+pub async fn revoke_api_key(
+    svc: web::Data<ApiKeyService>,
+    user: AuthUser,
+    id: web::Path<Uuid>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::UsersManage)?;
+
+    let api_key = svc.revoke(user.id, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("API key revoked", Some(api_key))))
+}
@@ -0,0 +1,36 @@
+use actix_web::{get, web, HttpResponse};
+use crate::services::audit_service::AuditService;
+use crate::dtos::audit_dto::AuditQuery;
+use crate::dtos::pagination_dto::PageRequest;
+use crate::errors::AppError;
+use crate::models::api_response::ApiResponse;
+use crate::models::audit::AuditEntry;
+use crate::openapi::ErrorResponse;
+use crate::extractors::auth::AuthUser;
+use crate::models::user::Permission;
+
+/// Audit trail, newest first, e.g. `?entity=project&id=<uuid>` for the history of one project.
+#[utoipa::path(
+    tag = "audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "One page of audit entries", body = ApiResponse<Vec<AuditEntry>>),
+        (status = 400, description = "Invalid query", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/api/audit")]
+pub async fn get_audit_log(
+    svc: web::Data<AuditService>,
+    user: AuthUser,
+    query: web::Query<AuditQuery>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::UsersManage)?;
+
+    let page = PageRequest::new(query.page, query.per_page, query.offset)?;
+    let result = svc.list(&query, page).await?;
+    let meta = result.meta(&page);
+    Ok(HttpResponse::Ok().json(ApiResponse::paginated(format!("{} audit entries found", result.total), result.items, meta)))
+}
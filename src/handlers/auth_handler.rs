@@ -0,0 +1,77 @@
+use actix_web::{get, post, web, HttpResponse};
+use crate::services::auth_service::AuthService;
+use crate::dtos::auth_dto::{CreateUserDTO, LoginDTO, TokenDTO};
+use crate::errors::AppError;
+use crate::extractors::auth::AuthUser;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::models::api_response::ApiResponse;
+use crate::models::user::User;
+use crate::openapi::ErrorResponse;
+use crate::models::user::Permission;
+
+#[utoipa::path(
+    tag = "auth",
+    request_body = LoginDTO,
+    responses(
+        (status = 200, description = "Logged in", body = ApiResponse<TokenDTO>),
+        (status = 401, description = "Wrong email or password", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    )
+)]
+#[post("/api/auth/login")]
+pub async fn login(
+    svc: web::Data<AuthService>,
+    body: ValidatedJson<LoginDTO>
+) -> Result<HttpResponse, AppError> {
+    let (user, token) = svc.login(&body.email, &body.password).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        "Login successful",
+        Some(TokenDTO {
+            access_token: token,
+            token_type: "Bearer".to_string(),
+            expires_in: svc.token_ttl_secs(),
+            user,
+        }),
+    )))
+}
+
+#[utoipa::path(
+    tag = "auth",
+    responses(
+        (status = 200, description = "The current user", body = ApiResponse<User>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/api/auth/me")]
+pub async fn me(
+    svc: web::Data<AuthService>,
+    user: AuthUser
+) -> Result<HttpResponse, AppError> {
+    let user = svc.get_by_id(user.id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("User found", Some(user))))
+}
+
+#[utoipa::path(
+    tag = "auth",
+    request_body = CreateUserDTO,
+    responses(
+        (status = 200, description = "User created", body = ApiResponse<User>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 409, description = "Email already in use", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/api/auth/users")]
+pub async fn create_user(
+    svc: web::Data<AuthService>,
+    user: AuthUser,
+    body: ValidatedJson<CreateUserDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::UsersManage)?;
+
+    let created = svc.create_user(&body.email, &body.password, body.role).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("User created successfully", Some(created))))
+}
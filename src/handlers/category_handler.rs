@@ -0,0 +1,115 @@
+use actix_web::{get, post, put, delete, web, HttpResponse};
+use uuid::Uuid;
+use crate::services::category_service::CategoryService;
+use crate::dtos::category_dto::{CreateCategoryDTO, UpdateCategoryDTO};
+use crate::errors::AppError;
+use crate::models::api_response::ApiResponse;
+use crate::models::category::Category;
+use crate::openapi::{ErrorResponse, MessageResponse};
+use crate::extractors::auth::AuthUser;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::models::user::Permission;
+
+#[utoipa::path(
+    tag = "categories",
+    request_body = CreateCategoryDTO,
+    responses(
+        (status = 200, description = "Category added", body = ApiResponse<Category>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 409, description = "Slug already in use", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/api/categories")]
+pub async fn add_category(
+    svc: web::Data<CategoryService>,
+    user: AuthUser,
+    body: ValidatedJson<CreateCategoryDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let category = svc.add(body.0).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Category added successfully", Some(category))))
+}
+
+/// Every category with its project count, in display order.
+#[utoipa::path(
+    tag = "categories",
+    responses(
+        (status = 200, description = "Every category", body = ApiResponse<Vec<Category>>)
+    )
+)]
+#[get("/api/categories")]
+pub async fn get_all_categories(
+    svc: web::Data<CategoryService>
+) -> Result<HttpResponse, AppError> {
+    let categories = svc.list().await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(format!("{} categories found", categories.len()), Some(categories))))
+}
+
+#[utoipa::path(
+    tag = "categories",
+    responses(
+        (status = 200, description = "Category found", body = ApiResponse<Category>),
+        (status = 404, description = "Category not found", body = ErrorResponse)
+    )
+)]
+#[get("/api/categories/{id}")]
+pub async fn get_category_by_id(
+    svc: web::Data<CategoryService>,
+    id: web::Path<Uuid>
+) -> Result<HttpResponse, AppError> {
+    let category = svc.get_by_id(id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Category found", Some(category))))
+}
+
+#[utoipa::path(
+    tag = "categories",
+    request_body = UpdateCategoryDTO,
+    responses(
+        (status = 200, description = "Category updated", body = ApiResponse<Category>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Category not found", body = ErrorResponse),
+        (status = 409, description = "Slug already in use", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[put("/api/categories/{id}")]
+pub async fn update_category(
+    svc: web::Data<CategoryService>,
+    user: AuthUser,
+    id: web::Path<Uuid>,
+    body: ValidatedJson<UpdateCategoryDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let category = svc.update(id.into_inner(), body.0).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Category updated successfully", Some(category))))
+}
+
+#[utoipa::path(
+    tag = "categories",
+    responses(
+        (status = 200, description = "Category deleted", body = MessageResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Category not found", body = ErrorResponse),
+        (status = 409, description = "Category still has projects", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[delete("/api/categories/{id}")]
+pub async fn delete_category(
+    svc: web::Data<CategoryService>,
+    user: AuthUser,
+    id: web::Path<Uuid>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    svc.delete(id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success("Category deleted successfully", None)))
+}
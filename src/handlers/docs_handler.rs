@@ -0,0 +1,36 @@
+use actix_web::{get, HttpResponse};
+use utoipa::OpenApi;
+use crate::openapi::ApiDoc;
+
+/// The OpenAPI 3 document, e.g. for generating typed clients.
+#[utoipa::path(
+    tag = "docs",
+    responses((status = 200, description = "OpenAPI 3 document", content_type = "application/json"))
+)]
+#[get("/api/openapi.json")]
+pub async fn openapi_json() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
+
+/// Interactive documentation rendered by Redoc from `/api/openapi.json`.
+#[get("/api/docs")]
+pub async fn api_docs() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(REDOC_PAGE)
+}
+
+const REDOC_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+  <head>
+    <title>Portfolio API</title>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <style>body { margin: 0; padding: 0; }</style>
+  </head>
+  <body>
+    <redoc spec-url="/api/openapi.json"></redoc>
+    <script src="https://cdn.redoc.ly/redoc/v2.1.5/bundles/redoc.standalone.js"></script>
+  </body>
+</html>
+"#;
@@ -1,113 +1,157 @@
-use actix_web::{get, post, put, delete, web, HttpResponse, Responder};
+use actix_web::{get, post, put, delete, web, HttpResponse};
 use uuid::Uuid;
 use crate::services::employee_service::EmployeeService;
-use crate::dtos::employee_dto::{CreateEmployeeDTO, UpdateEmployeeDTO};
+use crate::dtos::employee_dto::{CreateEmployeeDTO, EmployeeListQuery, UpdateEmployeeDTO};
+use crate::dtos::pagination_dto::PageRequest;
+use crate::errors::AppError;
+use crate::models::api_response::ApiResponse;
 use crate::models::employee::Employee;
-use serde::Serialize;
-
-#[derive(Serialize)]
-struct ApiResponse<T> {
-    status: String,
-    message: String,
-    data: Option<T>,
-}
+use crate::openapi::{ErrorResponse, MessageResponse};
+use crate::models::display::localize_all;
+use crate::dtos::display_dto::DisplayQuery;
+use crate::extractors::auth::AuthUser;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::models::user::Permission;
 
+#[utoipa::path(
+    tag = "employees",
+    request_body = CreateEmployeeDTO,
+    responses(
+        (status = 200, description = "Employee added", body = ApiResponse<Employee>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 409, description = "Email already in use", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[post("/api/employees")]
 pub async fn add_employee(
     svc: web::Data<EmployeeService>,
-    body: web::Json<CreateEmployeeDTO>
-) -> impl Responder {
-    match svc.add(body.0).await {
-        Ok(employee) => HttpResponse::Ok().json(ApiResponse {
-            status: "success".to_string(),
-            message: "Employee added successfully".to_string(),
-            data: Some(employee),
-        }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
-    }
+    user: AuthUser,
+    body: ValidatedJson<CreateEmployeeDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::EmployeesWrite)?;
+
+    let employee = svc.add(user.id, body.0).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Employee added successfully", Some(employee))))
 }
 
+#[utoipa::path(
+    tag = "employees",
+    params(EmployeeListQuery),
+    responses(
+        (status = 200, description = "One page of employees; search results also carry `rank` and `highlights`", body = ApiResponse<Vec<Employee>>),
+        (status = 400, description = "Invalid query", body = ErrorResponse)
+    )
+)]
 #[get("/api/employees")]
 pub async fn get_all_employees(
-    svc: web::Data<EmployeeService>
-) -> impl Responder {
-    match svc.get_all().await {
-        Ok(list) => HttpResponse::Ok().json(ApiResponse {
-            status: "success".to_string(),
-            message: format!("{} employees found", list.len()),
-            data: Some(list),
-        }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<Vec<Employee>> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
+    svc: web::Data<EmployeeService>,
+    query: web::Query<EmployeeListQuery>
+) -> Result<HttpResponse, AppError> {
+    let page = PageRequest::new(query.page, query.per_page, query.offset)?;
+    let display = query.display()?;
+
+    if let Some(term) = query.search_term() {
+        let mut result = svc.search(term, &query, page).await?;
+        localize_all(&mut result.items, display);
+        let meta = result.meta(&page);
+        return Ok(HttpResponse::Ok().json(ApiResponse::paginated(format!("{} employees found", result.total), result.items, meta)));
     }
+
+    let mut result = svc.list(&query, page).await?;
+    localize_all(&mut result.items, display);
+    let meta = result.meta(&page);
+
+    Ok(HttpResponse::Ok().json(ApiResponse::paginated(format!("{} employees found", result.total), result.items, meta)))
 }
 
+#[utoipa::path(
+    tag = "employees",
+    params(DisplayQuery),
+    responses(
+        (status = 200, description = "Employee found", body = ApiResponse<Employee>),
+        (status = 404, description = "Employee not found", body = ErrorResponse)
+    )
+)]
 #[get("/api/employees/{id}")]
 pub async fn get_employee_by_id(
     svc: web::Data<EmployeeService>,
-    id: web::Path<Uuid>
-) -> impl Responder {
-    match svc.get_by_id(id.into_inner()).await {
-        Ok(employee) => HttpResponse::Ok().json(ApiResponse {
-            status: "success".to_string(),
-            message: "Employee found".to_string(),
-            data: Some(employee),
-        }),
-        Err(err) => HttpResponse::NotFound().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
-    }
+    id: web::Path<Uuid>,
+    query: web::Query<DisplayQuery>
+) -> Result<HttpResponse, AppError> {
+    let mut employee = svc.get_by_id(id.into_inner()).await?;
+    localize_all([&mut employee], query.display()?);
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Employee found", Some(employee))))
 }
 
+#[utoipa::path(
+    tag = "employees",
+    request_body = UpdateEmployeeDTO,
+    responses(
+        (status = 200, description = "Employee updated", body = ApiResponse<Employee>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Employee not found", body = ErrorResponse),
+        (status = 409, description = "Email already in use", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[put("/api/employees/{id}")]
 pub async fn update_employee(
     svc: web::Data<EmployeeService>,
+    user: AuthUser,
     id: web::Path<Uuid>,
-    body: web::Json<UpdateEmployeeDTO>
-) -> impl Responder {
-    match svc.update(id.into_inner(), body.0).await {
-        Ok(employee) => HttpResponse::Ok().json(ApiResponse {
-            status: "success".to_string(),
-            message: "Employee updated successfully".to_string(),
-            data: Some(employee),
-        }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
-    }
+    body: ValidatedJson<UpdateEmployeeDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::EmployeesWrite)?;
+
+    let employee = svc.update(user.id, id.into_inner(), body.0).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Employee updated successfully", Some(employee))))
 }
 
+#[utoipa::path(
+    tag = "employees",
+    responses(
+        (status = 200, description = "Employee moved to trash", body = MessageResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Employee not found", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[delete("/api/employees/{id}")]
 pub async fn delete_employee(
     svc: web::Data<EmployeeService>,
+    user: AuthUser,
     id: web::Path<Uuid>
-) -> impl Responder {
-    match svc.delete(id.into_inner()).await {
-        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
-            status: "success".to_string(),
-            message: "Employee deleted successfully".to_string(),
-            data: None,
-        }),
-        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: "Employee not found".to_string(),
-            data: None,
-        }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
-    }
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::EmployeesWrite)?;
+
+    svc.delete(user.id, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success("Employee moved to trash", None)))
+}
+
+#[utoipa::path(
+    tag = "employees",
+    responses(
+        (status = 200, description = "Employee restored", body = ApiResponse<Employee>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Employee not in trash", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/api/employees/{id}/restore")]
+pub async fn restore_employee(
+    svc: web::Data<EmployeeService>,
+    user: AuthUser,
+    id: web::Path<Uuid>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::EmployeesWrite)?;
+
+    let employee = svc.restore(user.id, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Employee restored successfully", Some(employee))))
 }
@@ -0,0 +1,53 @@
+use actix_web::{get, web, HttpResponse};
+use crate::models::health::{HealthStatus, Liveness, Readiness, VersionInfo};
+use crate::services::health_service::HealthService;
+
+// Probe endpoints answer with bare JSON instead of the `ApiResponse` envelope and are
+// never cached, so a stale answer can't hide an outage.
+
+/// Liveness: answers as long as the server is able to handle requests.
+#[utoipa::path(
+    tag = "health",
+    responses((status = 200, description = "The process is alive", body = Liveness))
+)]
+#[get("/healthz")]
+pub async fn healthz() -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(Liveness { status: HealthStatus::Ok })
+}
+
+/// Readiness: a database connection can be obtained from the pool and the image storage
+/// is configured.
+#[utoipa::path(
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready to serve traffic", body = Readiness),
+        (status = 503, description = "A check failed", body = Readiness)
+    )
+)]
+#[get("/readyz")]
+pub async fn readyz(
+    svc: web::Data<HealthService>
+) -> HttpResponse {
+    let readiness = svc.readiness().await;
+    let mut response = if readiness.status == HealthStatus::Ok {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::ServiceUnavailable()
+    };
+    response.insert_header(("Cache-Control", "no-store")).json(readiness)
+}
+
+#[utoipa::path(
+    tag = "health",
+    responses((status = 200, description = "Build information", body = VersionInfo))
+)]
+#[get("/version")]
+pub async fn get_version() -> HttpResponse {
+    HttpResponse::Ok().json(VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: env!("GIT_SHA"),
+        build_time: env!("BUILD_TIME"),
+    })
+}
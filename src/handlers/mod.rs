@@ -1,2 +1,9 @@
+pub mod api_key_handler;
+pub mod audit_handler;
+pub mod auth_handler;
+pub mod category_handler;
+pub mod docs_handler;
 pub mod employee_handler;
-pub mod project_handler;
\ No newline at end of file
+pub mod health_handler;
+pub mod project_handler;
+pub mod trash_handler;
@@ -1,362 +1,759 @@
-use actix_web::{get, post, put, delete, web, HttpResponse, Responder};
-use actix_multipart::Multipart;
+use actix_web::{get, post, put, patch, delete, http::header, web, HttpRequest, HttpResponse};
+use actix_multipart::{Field, Multipart};
 use futures_util::stream::StreamExt as _;
 use uuid::Uuid;
-use std::io::Write;
 use crate::services::project_service::ProjectService;
-use crate::dtos::project_dto::{CreateProjectDTO, UpdateProjectDTO};
-use crate::models::project::Project;
-use serde::Serialize;
-
-#[derive(Serialize)]
-struct ApiResponse<T> {
-    status: String,
-    message: String,
-    data: Option<T>,
-}
+use crate::models::project::{Project, ProjectStatus, SlugLookup};
+use crate::services::image_type::{sniff, ImageFormat, SNIFF_LEN};
+use crate::config::{AppConfig, UploadLimits};
+use crate::dtos::project_dto::{CreateProjectDTO, NewProjectImageDTO, ProjectListQuery, ReorderImagesDTO, RevisionDiffQuery, UpdateProjectDTO, UpdateProjectImageDTO};
+use crate::dtos::pagination_dto::PageRequest;
+use crate::errors::AppError;
+use crate::validation::{field_errors, FieldErrors};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use crate::models::api_response::ApiResponse;
+use crate::models::project::ProjectImage;
+use crate::models::revision::{ProjectRevision, RevisionDiff};
+use crate::models::tag::TagCount;
+use crate::openapi::{CreateProjectForm, ErrorResponse, ImageUploadForm, MessageResponse, ProjectImageUploadForm};
+use crate::models::display::localize_all;
+use crate::dtos::display_dto::DisplayQuery;
+use crate::extractors::auth::AuthUser;
+use crate::extractors::validated_json::ValidatedJson;
+use crate::models::user::Permission;
 
+#[utoipa::path(
+    tag = "projects",
+    request_body = CreateProjectDTO,
+    responses(
+        (status = 200, description = "Project added", body = ApiResponse<Project>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[post("/api/projects")]
 pub async fn add_project(
     svc: web::Data<ProjectService>,
-    body: web::Json<CreateProjectDTO>
-) -> impl Responder {
-    // Validate that images array is not empty
-    if body.images.is_empty() {
-        return HttpResponse::BadRequest().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: "At least one image is required".to_string(),
-            data: None,
-        });
-    }
-    
-    match svc.add(body.0).await {
-        Ok(project) => HttpResponse::Ok().json(ApiResponse {
-            status: "success".to_string(),
-            message: "Project added successfully".to_string(),
-            data: Some(project),
-        }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
-    }
+    user: AuthUser,
+    body: ValidatedJson<CreateProjectDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let project = svc.add(user.id, body.0).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Project added successfully", Some(project))))
 }
 
+#[utoipa::path(
+    tag = "projects",
+    request_body(content = ImageUploadForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Image stored; send it back in `images`", body = ApiResponse<NewProjectImageDTO>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 400, description = "No file uploaded", body = ErrorResponse),
+        (status = 413, description = "Upload too large", body = ErrorResponse),
+        (status = 415, description = "Not a supported image", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[post("/api/projects/upload")]
 pub async fn upload_project_image(
+    svc: web::Data<ProjectService>,
+    config: web::Data<AppConfig>,
+    user: AuthUser,
+    req: HttpRequest,
     mut payload: Multipart,
-) -> impl Responder {
-    while let Some(item) = payload.next().await {
-        let mut field = match item {
-            Ok(field) => field,
-            Err(e) => {
-                return HttpResponse::BadRequest().json(ApiResponse::<()> {
-                    status: "error".to_string(),
-                    message: format!("Error reading multipart: {}", e),
-                    data: None,
-                });
-            }
-        };
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+    let mut budget = UploadBudget::new(&req, &config.uploads)?;
 
-        let content_disposition = field.content_disposition();
-        let filename = content_disposition
-            .get_filename()
-            .map(|f| sanitize_filename::sanitize(f))
-            .unwrap_or_else(|| format!("{}.png", Uuid::new_v4()));
-
-        let filepath = format!("./uploads/{}", filename);
-
-        // Create uploads directory if not exists
-        std::fs::create_dir_all("./uploads").unwrap_or_default();
-
-        let mut f = match web::block(move || std::fs::File::create(filepath.clone()))
-            .await
-        {
-            Ok(Ok(file)) => file,
-            Ok(Err(e)) => {
-                return HttpResponse::InternalServerError().json(ApiResponse::<()> {
-                    status: "error".to_string(),
-                    message: format!("Error creating file: {}", e),
-                    data: None,
-                });
-            }
-            Err(e) => {
-                return HttpResponse::InternalServerError().json(ApiResponse::<()> {
-                    status: "error".to_string(),
-                    message: format!("Blocking error: {}", e),
-                    data: None,
-                });
-            }
-        };
+    // Only the first file in the form is stored
+    let Some(item) = payload.next().await else {
+        return Err(AppError::BadRequest("No file uploaded".to_string()));
+    };
+    let mut field = item.map_err(|e| AppError::BadRequest(format!("Error reading multipart: {}", e)))?;
 
-        while let Some(chunk) = field.next().await {
-            let data = match chunk {
-                Ok(data) => data,
-                Err(e) => {
-                    return HttpResponse::InternalServerError().json(ApiResponse::<()> {
-                        status: "error".to_string(),
-                        message: format!("Error reading chunk: {}", e),
-                        data: None,
-                    });
-                }
-            };
-            
-            if let Err(e) = f.write_all(&data) {
-                return HttpResponse::InternalServerError().json(ApiResponse::<()> {
-                    status: "error".to_string(),
-                    message: format!("Error writing file: {}", e),
-                    data: None,
-                });
-            }
-        }
+    let (image_data, format) = budget.read_image(&mut field).await?;
+    let basename = storage_basename(0);
 
-        return HttpResponse::Ok().json(ApiResponse {
-            status: "success".to_string(),
-            message: "Image uploaded successfully".to_string(),
-            data: Some(serde_json::json!({
-                "filename": filename,
-                "url": format!("/uploads/{}", filename)
-            })),
-        });
-    }
+    // Ready to be sent back in `images` of `POST /api/projects` or `POST /api/projects/{id}/images`
+    let image = svc.upload_image(image_data, format, &basename).await?;
 
-    HttpResponse::BadRequest().json(ApiResponse::<()> {
-        status: "error".to_string(),
-        message: "No file uploaded".to_string(),
-        data: None,
-    })
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Image uploaded successfully", Some(image))))
 }
 
+#[utoipa::path(
+    tag = "projects",
+    request_body(content = CreateProjectForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Project created", body = ApiResponse<Project>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 413, description = "Upload too large", body = ErrorResponse),
+        (status = 415, description = "Not a supported image", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[post("/api/projects/create-with-upload")]
 pub async fn create_project_with_upload(
     svc: web::Data<ProjectService>,
+    config: web::Data<AppConfig>,
+    user: AuthUser,
+    req: HttpRequest,
     mut payload: Multipart,
-) -> impl Responder {
-    use crate::services::cloudinary::CloudinaryService;
-    
-    let mut name: Option<String> = None;
-    let mut description: Option<String> = None;
-    let mut category: Option<String> = None;
-    let mut image_urls: Vec<String> = Vec::new();
-    
-    let cloudinary = match CloudinaryService::new() {
-        Ok(service) => service,
-        Err(e) => {
-            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
-                status: "error".to_string(),
-                message: format!("Cloudinary not configured: {}. Please set CLOUDINARY_CLOUD_NAME environment variable.", e),
-                data: None,
-            });
-        }
-    };
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+    let mut budget = UploadBudget::new(&req, &config.uploads)?;
 
-    while let Some(item) = payload.next().await {
-        let mut field = match item {
-            Ok(field) => field,
-            Err(e) => {
-                return HttpResponse::BadRequest().json(ApiResponse::<()> {
-                    status: "error".to_string(),
-                    message: format!("Error reading multipart: {}", e),
-                    data: None,
-                });
-            }
-        };
+    let mut images: Vec<NewProjectImageDTO> = Vec::new();
+    // Whatever fails after the first upload, the files already stored must not be orphaned
+    let created = async {
+        let mut name: Option<String> = None;
+        let mut description: Option<String> = None;
+        let mut category: Option<String> = None;
+        let mut tags: Vec<String> = Vec::new();
+        let mut status: Option<ProjectStatus> = None;
+        let mut publish_at: Option<DateTime<Utc>> = None;
+        // Text fields that fail to parse, reported together with the DTO validation
+        let mut invalid = FieldErrors::new();
 
-        let content_disposition = field.content_disposition();
-        let field_name = content_disposition.get_name().unwrap_or("");
-
-        match field_name {
-            "name" => {
-                let mut bytes = web::BytesMut::new();
-                while let Some(chunk) = field.next().await {
-                    let data = chunk.unwrap();
-                    bytes.extend_from_slice(&data);
-                }
-                name = Some(String::from_utf8(bytes.to_vec()).unwrap_or_default());
-            }
-            "description" => {
-                let mut bytes = web::BytesMut::new();
-                while let Some(chunk) = field.next().await {
-                    let data = chunk.unwrap();
-                    bytes.extend_from_slice(&data);
+        while let Some(item) = payload.next().await {
+            let mut field = item.map_err(|e| AppError::BadRequest(format!("Error reading multipart: {}", e)))?;
+
+            let content_disposition = field.content_disposition();
+            let field_name = content_disposition.get_name().unwrap_or("");
+
+            match field_name {
+                "name" | "description" | "category" => {
+                    let target = match field_name {
+                        "name" => &mut name,
+                        "description" => &mut description,
+                        _ => &mut category,
+                    };
+                    *target = Some(budget.read_text(&mut field).await?);
                 }
-                description = Some(String::from_utf8(bytes.to_vec()).unwrap_or_default());
-            }
-            "category" => {
-                let mut bytes = web::BytesMut::new();
-                while let Some(chunk) = field.next().await {
-                    let data = chunk.unwrap();
-                    bytes.extend_from_slice(&data);
+                // Either one comma separated field or the field repeated per tag
+                "tags" | "tags[]" => {
+                    let value = budget.read_text(&mut field).await?;
+                    tags.extend(value.split(',').map(str::trim).filter(|t| !t.is_empty()).map(String::from));
                 }
-                category = Some(String::from_utf8(bytes.to_vec()).unwrap_or_default());
-            }
-            "file" | "image" | "files" | "images" => {
-                let original_filename = content_disposition
-                    .get_filename()
-                    .map(|f| sanitize_filename::sanitize(f))
-                    .unwrap_or_else(|| format!("{}.png", Uuid::new_v4()));
-                
-                let extension = original_filename
-                    .rsplit('.')
-                    .next()
-                    .unwrap_or("png");
-                
-                let index = image_urls.len();
-                let filename = format!("{}_{:03}_{}.{}", 
-                    Uuid::new_v4(), 
-                    index, 
-                    chrono::Utc::now().timestamp_millis(),
-                    extension
-                );
-
-                // Collect image data
-                let mut image_data = Vec::new();
-                while let Some(chunk) = field.next().await {
-                    let data = match chunk {
-                        Ok(data) => data,
-                        Err(e) => {
-                            return HttpResponse::InternalServerError().json(ApiResponse::<()> {
-                                status: "error".to_string(),
-                                message: format!("Error reading chunk: {}", e),
-                                data: None,
-                            });
+                "status" => match budget.read_text(&mut field).await?.trim().parse() {
+                    Ok(value) => status = Some(value),
+                    Err(_) => {
+                        invalid.insert("status".to_string(), vec!["must be draft, published or archived".to_string()]);
+                    }
+                },
+                "publish_at" => {
+                    let value = budget.read_text(&mut field).await?;
+                    if !value.trim().is_empty() {
+                        match DateTime::parse_from_rfc3339(value.trim()) {
+                            Ok(value) => publish_at = Some(value.with_timezone(&Utc)),
+                            Err(_) => {
+                                invalid.insert("publish_at".to_string(), vec!["must be an RFC 3339 timestamp".to_string()]);
+                            }
                         }
-                    };
-                    image_data.extend_from_slice(&data);
+                    }
                 }
+                "file" | "image" | "files" | "images" => {
+                    let (image_data, format) = budget.read_image(&mut field).await?;
+                    // Index prefix keeps the upload order visible in the stored file names
+                    let basename = storage_basename(images.len());
 
-                // Upload to Cloudinary
-                match cloudinary.upload_image(image_data, filename).await {
-                    Ok(url) => {
-                        image_urls.push(url);
-                    }
-                    Err(e) => {
-                        return HttpResponse::InternalServerError().json(ApiResponse::<()> {
-                            status: "error".to_string(),
-                            message: format!("Failed to upload image to Cloudinary: {}", e),
-                            data: None,
-                        });
-                    }
+                    images.push(svc.upload_image(image_data, format, &basename).await?);
                 }
+                _ => {}
             }
-            _ => {}
         }
-    }
-
-    if name.is_none() || description.is_none() || category.is_none() || image_urls.is_empty() {
-        return HttpResponse::BadRequest().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: "Missing required fields: name, description, category, and at least one file are required".to_string(),
-            data: None,
-        });
-    }
 
-    let dto = CreateProjectDTO {
-        name: name.unwrap(),
-        description: description.unwrap(),
-        images: image_urls,
-        category: category.unwrap(),
-    };
+        // Missing text fields fail the same `not_blank` rules as an empty JSON string would
+        let dto = CreateProjectDTO {
+            name: name.unwrap_or_default(),
+            description: description.unwrap_or_default(),
+            images: images.clone(),
+            category: category.unwrap_or_default(),
+            tags,
+            status,
+            publish_at,
+        };
+        let mut errors = dto.validate().err().map(|e| field_errors(&e)).unwrap_or_default();
+        errors.extend(invalid);
+        if !errors.is_empty() {
+            return Err(AppError::InvalidFields(errors));
+        }
 
-    match svc.add(dto).await {
-        Ok(project) => HttpResponse::Ok().json(ApiResponse {
-            status: "success".to_string(),
-            message: "Project created successfully with uploaded images".to_string(),
-            data: Some(project),
-        }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
+        svc.add(user.id, dto).await
     }
+    .await;
+    let project = match created {
+        Ok(project) => project,
+        Err(e) => {
+            svc.discard_uploads(&images).await;
+            return Err(e);
+        }
+    };
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Project created successfully with uploaded images", Some(project))))
 }
 
+/// Anonymous callers (and users who can't edit projects) only see published projects;
+/// editors and admins see every status and may filter with `?status=`.
+#[utoipa::path(
+    tag = "projects",
+    params(ProjectListQuery),
+    responses(
+        (status = 200, description = "One page of projects; search results also carry `rank` and `highlights`", body = ApiResponse<Vec<Project>>),
+        (status = 400, description = "Invalid query", body = ErrorResponse)
+    ),
+    security((), ("bearer_auth" = []), ("api_key" = []))
+)]
 #[get("/api/projects")]
 pub async fn get_all_projects(
     svc: web::Data<ProjectService>,
-    query: web::Query<std::collections::HashMap<String, String>>
-) -> impl Responder {
-    let result = if let Some(category) = query.get("category") {
-        svc.get_by_category(category.clone()).await
-    } else {
-        svc.get_all().await
-    };
+    user: Option<AuthUser>,
+    query: web::Query<ProjectListQuery>
+) -> Result<HttpResponse, AppError> {
+    let mut query = query.into_inner();
+    if !can_see_unpublished(&user) {
+        query.status = Some(ProjectStatus::Published);
+    }
+    let page = PageRequest::new(query.page, query.per_page, query.offset)?;
+    let display = query.display()?;
 
-    match result {
-        Ok(list) => HttpResponse::Ok().json(ApiResponse {
-            status: "success".to_string(),
-            message: format!("{} projects found", list.len()),
-            data: Some(list),
-        }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<Vec<Project>> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
+    if let Some(term) = query.search_term() {
+        let mut result = svc.search(term, &query, page).await?;
+        localize_all(&mut result.items, display);
+        let meta = result.meta(&page);
+        return Ok(HttpResponse::Ok().json(ApiResponse::paginated(format!("{} projects found", result.total), result.items, meta)));
     }
+
+    let mut result = svc.list(&query, page).await?;
+    localize_all(&mut result.items, display);
+    let meta = result.meta(&page);
+
+    Ok(HttpResponse::Ok().json(ApiResponse::paginated(format!("{} projects found", result.total), result.items, meta)))
 }
 
+/// Tag cloud: every tag in use with its project count.
+#[utoipa::path(
+    tag = "projects",
+    responses(
+        (status = 200, description = "Tags of published projects", body = ApiResponse<Vec<TagCount>>)
+    )
+)]
+#[get("/api/tags")]
+pub async fn get_tag_cloud(
+    svc: web::Data<ProjectService>
+) -> Result<HttpResponse, AppError> {
+    let tags = svc.tag_cloud().await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(format!("{} tags found", tags.len()), Some(tags))))
+}
+
+#[utoipa::path(
+    tag = "projects",
+    params(DisplayQuery),
+    responses(
+        (status = 200, description = "Project found", body = ApiResponse<Project>),
+        (status = 404, description = "Project not found", body = ErrorResponse)
+    ),
+    security((), ("bearer_auth" = []), ("api_key" = []))
+)]
 #[get("/api/projects/{id}")]
 pub async fn get_project_by_id(
     svc: web::Data<ProjectService>,
-    id: web::Path<Uuid>
-) -> impl Responder {
-    match svc.get_by_id(id.into_inner()).await {
-        Ok(project) => HttpResponse::Ok().json(ApiResponse {
-            status: "success".to_string(),
-            message: "Project found".to_string(),
-            data: Some(project),
-        }),
-        Err(err) => HttpResponse::NotFound().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
+    user: Option<AuthUser>,
+    id: web::Path<Uuid>,
+    query: web::Query<DisplayQuery>
+) -> Result<HttpResponse, AppError> {
+    let mut project = visible(svc.get_by_id(id.into_inner()).await?, &user)?;
+    localize_all([&mut project], query.display()?);
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Project found", Some(project))))
+}
+
+/// Old slugs of renamed projects answer with a permanent redirect to the current one.
+#[utoipa::path(
+    tag = "projects",
+    params(DisplayQuery),
+    responses(
+        (status = 200, description = "Project found", body = ApiResponse<Project>),
+        (status = 301, description = "Old slug of a renamed project", headers(("Location" = String, description = "URL of the current slug"))),
+        (status = 404, description = "Project not found", body = ErrorResponse)
+    ),
+    security((), ("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/api/projects/by-slug/{slug}")]
+pub async fn get_project_by_slug(
+    svc: web::Data<ProjectService>,
+    user: Option<AuthUser>,
+    req: HttpRequest,
+    slug: web::Path<String>,
+    query: web::Query<DisplayQuery>
+) -> Result<HttpResponse, AppError> {
+    // Hidden projects answer like missing ones, whether the slug is current or old
+    let not_found = |_| AppError::NotFound(format!("Project '{}' not found", slug));
+    match svc.get_by_slug(&slug).await? {
+        SlugLookup::Current(project) => {
+            let mut project = visible(*project, &user).map_err(not_found)?;
+            localize_all([&mut project], query.display()?);
+            Ok(HttpResponse::Ok().json(ApiResponse::success("Project found", Some(project))))
+        }
+        SlugLookup::Moved(project) => {
+            let project = visible(*project, &user).map_err(not_found)?;
+            let mut location = format!("/api/projects/by-slug/{}", percent_encode(&project.slug));
+            if !req.query_string().is_empty() {
+                location = format!("{}?{}", location, req.query_string());
+            }
+            Ok(HttpResponse::MovedPermanently()
+                .insert_header((header::LOCATION, location))
+                .json(ApiResponse::success("Project moved", Some(serde_json::json!({ "slug": project.slug })))))
+        }
     }
 }
 
+#[utoipa::path(
+    tag = "projects",
+    request_body = UpdateProjectDTO,
+    responses(
+        (status = 200, description = "Project updated", body = ApiResponse<Project>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[put("/api/projects/{id}")]
 pub async fn update_project(
     svc: web::Data<ProjectService>,
+    user: AuthUser,
     id: web::Path<Uuid>,
-    body: web::Json<UpdateProjectDTO>
-) -> impl Responder {
-    match svc.update(id.into_inner(), body.0).await {
-        Ok(project) => HttpResponse::Ok().json(ApiResponse {
-            status: "success".to_string(),
-            message: "Project updated successfully".to_string(),
-            data: Some(project),
-        }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
-    }
+    body: ValidatedJson<UpdateProjectDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let project = svc.update(user.id, id.into_inner(), body.0).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Project updated successfully", Some(project))))
 }
 
+#[utoipa::path(
+    tag = "projects",
+    responses(
+        (status = 200, description = "Project moved to trash", body = MessageResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
 #[delete("/api/projects/{id}")]
 pub async fn delete_project(
     svc: web::Data<ProjectService>,
+    user: AuthUser,
+    id: web::Path<Uuid>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    svc.delete(user.id, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success("Project moved to trash", None)))
+}
+
+#[utoipa::path(
+    tag = "projects",
+    responses(
+        (status = 200, description = "Project restored", body = ApiResponse<Project>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Project not in trash", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/api/projects/{id}/restore")]
+pub async fn restore_project(
+    svc: web::Data<ProjectService>,
+    user: AuthUser,
     id: web::Path<Uuid>
-) -> impl Responder {
-    match svc.delete(id.into_inner()).await {
-        Ok(deleted_count) if deleted_count > 0 => HttpResponse::Ok().json(ApiResponse::<()> {
-            status: "success".to_string(),
-            message: "Project deleted successfully".to_string(),
-            data: None,
-        }),
-        Ok(_) => HttpResponse::NotFound().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: "Project not found".to_string(),
-            data: None,
-        }),
-        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()> {
-            status: "error".to_string(),
-            message: err,
-            data: None,
-        }),
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let project = svc.restore(user.id, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Project restored successfully", Some(project))))
+}
+
+#[utoipa::path(
+    tag = "projects",
+    request_body = NewProjectImageDTO,
+    responses(
+        (status = 200, description = "Image added", body = ApiResponse<ProjectImage>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/api/projects/{id}/images")]
+pub async fn add_project_image(
+    svc: web::Data<ProjectService>,
+    user: AuthUser,
+    id: web::Path<Uuid>,
+    body: ValidatedJson<NewProjectImageDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let image = svc.add_image(user.id, id.into_inner(), body.0).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Image added successfully", Some(image))))
+}
+
+/// Multipart form with one `file` plus optional `alt`, `caption` and `is_cover` fields.
+#[utoipa::path(
+    tag = "projects",
+    request_body(content = ProjectImageUploadForm, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Image uploaded and added", body = ApiResponse<ProjectImage>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+        (status = 413, description = "Upload too large", body = ErrorResponse),
+        (status = 415, description = "Not a supported image", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/api/projects/{id}/images/upload")]
+pub async fn upload_and_add_project_image(
+    svc: web::Data<ProjectService>,
+    config: web::Data<AppConfig>,
+    user: AuthUser,
+    id: web::Path<Uuid>,
+    req: HttpRequest,
+    mut payload: Multipart,
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+    let project_id = id.into_inner();
+    // Fail before processing the upload when the project doesn't exist
+    let project = svc.get_by_id(project_id).await?;
+    let mut budget = UploadBudget::new(&req, &config.uploads)?;
+
+    let mut file: Option<(Vec<u8>, ImageFormat)> = None;
+    let mut alt: Option<String> = None;
+    let mut caption: Option<String> = None;
+    let mut is_cover = false;
+
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|e| AppError::BadRequest(format!("Error reading multipart: {}", e)))?;
+
+        let content_disposition = field.content_disposition();
+        let field_name = content_disposition.get_name().unwrap_or("").to_string();
+
+        match field_name.as_str() {
+            "alt" => alt = Some(budget.read_text(&mut field).await?),
+            "caption" => caption = Some(budget.read_text(&mut field).await?),
+            "is_cover" => is_cover = budget.read_text(&mut field).await?.trim() == "true",
+            "file" | "image" if file.is_none() => file = Some(budget.read_image(&mut field).await?),
+            _ => {}
+        }
+    }
+
+    let Some((image_data, format)) = file else {
+        return Err(AppError::InvalidFields(FieldErrors::from([(
+            "file".to_string(),
+            vec!["is required".to_string()],
+        )])));
+    };
+    let basename = storage_basename(project.images.len());
+    let uploaded = svc.upload_image(image_data, format, &basename).await?;
+
+    let dto = NewProjectImageDTO { alt, caption, is_cover, ..uploaded };
+    if let Err(errors) = dto.validate() {
+        svc.discard_uploads(std::slice::from_ref(&dto)).await;
+        return Err(errors.into());
     }
+    let image = match svc.add_image(user.id, project_id, dto.clone()).await {
+        Ok(image) => image,
+        Err(e) => {
+            svc.discard_uploads(std::slice::from_ref(&dto)).await;
+            return Err(e);
+        }
+    };
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Image uploaded successfully", Some(image))))
+}
+
+#[utoipa::path(
+    tag = "projects",
+    request_body = ReorderImagesDTO,
+    responses(
+        (status = 200, description = "Images in their new order", body = ApiResponse<Vec<ProjectImage>>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[put("/api/projects/{id}/images/order")]
+pub async fn reorder_project_images(
+    svc: web::Data<ProjectService>,
+    user: AuthUser,
+    id: web::Path<Uuid>,
+    body: ValidatedJson<ReorderImagesDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let images = svc.reorder_images(user.id, id.into_inner(), body.0).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Images reordered successfully", Some(images))))
+}
+
+#[utoipa::path(
+    tag = "projects",
+    request_body = UpdateProjectImageDTO,
+    responses(
+        (status = 200, description = "Image updated", body = ApiResponse<ProjectImage>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Image not found", body = ErrorResponse),
+        (status = 422, description = "Validation failed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[patch("/api/projects/{id}/images/{image_id}")]
+pub async fn update_project_image(
+    svc: web::Data<ProjectService>,
+    user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>,
+    body: ValidatedJson<UpdateProjectImageDTO>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let (project_id, image_id) = path.into_inner();
+    let image = svc.update_image(user.id, project_id, image_id, body.0).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Image updated successfully", Some(image))))
+}
+
+#[utoipa::path(
+    tag = "projects",
+    responses(
+        (status = 200, description = "Image deleted", body = MessageResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Image not found", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[delete("/api/projects/{id}/images/{image_id}")]
+pub async fn delete_project_image(
+    svc: web::Data<ProjectService>,
+    user: AuthUser,
+    path: web::Path<(Uuid, Uuid)>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let (project_id, image_id) = path.into_inner();
+    svc.remove_image(user.id, project_id, image_id).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::<()>::success("Image deleted successfully", None)))
+}
+
+#[utoipa::path(
+    tag = "projects",
+    responses(
+        (status = 200, description = "Revisions, newest first", body = ApiResponse<Vec<ProjectRevision>>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Project not found", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/api/projects/{id}/revisions")]
+pub async fn get_project_revisions(
+    svc: web::Data<ProjectService>,
+    user: AuthUser,
+    id: web::Path<Uuid>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let revisions = svc.revisions(id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success(format!("{} revisions found", revisions.len()), Some(revisions))))
+}
+
+#[utoipa::path(
+    tag = "projects",
+    responses(
+        (status = 200, description = "Revision found", body = ApiResponse<ProjectRevision>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Revision not found", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/api/projects/{id}/revisions/{revision}")]
+pub async fn get_project_revision(
+    svc: web::Data<ProjectService>,
+    user: AuthUser,
+    path: web::Path<(Uuid, i32)>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let (project_id, revision) = path.into_inner();
+    let revision = svc.revision(project_id, revision).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Revision found", Some(revision))))
+}
+
+/// Changes from a revision to the current content, or to `?against=<revision>`.
+#[utoipa::path(
+    tag = "projects",
+    params(RevisionDiffQuery),
+    responses(
+        (status = 200, description = "Changes between the revisions", body = ApiResponse<RevisionDiff>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Revision not found", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/api/projects/{id}/revisions/{revision}/diff")]
+pub async fn diff_project_revision(
+    svc: web::Data<ProjectService>,
+    user: AuthUser,
+    path: web::Path<(Uuid, i32)>,
+    query: web::Query<RevisionDiffQuery>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let (project_id, revision) = path.into_inner();
+    let diff = svc.diff_revision(project_id, revision, query.against).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Revision diff", Some(diff))))
+}
+
+#[utoipa::path(
+    tag = "projects",
+    responses(
+        (status = 200, description = "Project restored to the revision", body = ApiResponse<Project>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Revision not found", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[post("/api/projects/{id}/revisions/{revision}/restore")]
+pub async fn restore_project_revision(
+    svc: web::Data<ProjectService>,
+    user: AuthUser,
+    path: web::Path<(Uuid, i32)>
+) -> Result<HttpResponse, AppError> {
+    user.require(Permission::ProjectsWrite)?;
+
+    let (project_id, revision) = path.into_inner();
+    let project = svc.restore_revision(user.id, project_id, revision).await?;
+    Ok(HttpResponse::Ok().json(ApiResponse::success("Project restored to revision", Some(project))))
+}
+
+/// Unique base name for a stored upload. Extensions are added per rendition from the
+/// encoded format, never taken from the client-provided file name.
+fn storage_basename(index: usize) -> String {
+    format!("{}_{:03}_{}",
+        Uuid::new_v4(),
+        index,
+        chrono::Utc::now().timestamp_millis()
+    )
+}
+
+/// Tracks how much of a multipart request has been read so the per-file and per-request
+/// limits are enforced while streaming instead of after buffering everything.
+struct UploadBudget<'a> {
+    limits: &'a UploadLimits,
+    used: usize,
+}
+
+impl<'a> UploadBudget<'a> {
+    fn new(req: &HttpRequest, limits: &'a UploadLimits) -> Result<Self, AppError> {
+        // Reject early when the client announces an oversized body
+        let declared = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        if declared.is_some_and(|len| len > limits.max_request_bytes) {
+            return Err(request_too_large(limits));
+        }
+        Ok(Self { limits, used: 0 })
+    }
+
+    fn consume(&mut self, len: usize) -> Result<(), AppError> {
+        self.used += len;
+        if self.used > self.limits.max_request_bytes {
+            return Err(request_too_large(self.limits));
+        }
+        Ok(())
+    }
+
+    async fn read_text(&mut self, field: &mut Field) -> Result<String, AppError> {
+        let mut bytes = web::BytesMut::new();
+        while let Some(chunk) = field.next().await {
+            let data = chunk.map_err(|e| AppError::BadRequest(format!("Error reading chunk: {}", e)))?;
+            self.consume(data.len())?;
+            bytes.extend_from_slice(&data);
+        }
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| AppError::BadRequest("Form fields must be valid UTF-8".to_string()))
+    }
+
+    async fn read_image(&mut self, field: &mut Field) -> Result<(Vec<u8>, ImageFormat), AppError> {
+        let mut data = Vec::new();
+        let mut format = None;
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| AppError::BadRequest(format!("Error reading chunk: {}", e)))?;
+            self.consume(chunk.len())?;
+            data.extend_from_slice(&chunk);
+            if data.len() > self.limits.max_file_bytes {
+                return Err(AppError::PayloadTooLarge(format!(
+                    "Each image must be at most {} MB",
+                    self.limits.max_file_bytes / (1024 * 1024)
+                )));
+            }
+            if format.is_none() && data.len() >= SNIFF_LEN {
+                format = Some(sniff(&data).ok_or_else(unsupported_type)?);
+            }
+        }
+        let format = match format {
+            Some(format) => format,
+            None => sniff(&data).ok_or_else(unsupported_type)?,
+        };
+        Ok((data, format))
+    }
+}
+
+fn request_too_large(limits: &UploadLimits) -> AppError {
+    AppError::PayloadTooLarge(format!(
+        "Upload request must be at most {} MB",
+        limits.max_request_bytes / (1024 * 1024)
+    ))
+}
+
+fn unsupported_type() -> AppError {
+    AppError::UnsupportedMediaType("Only JPEG, PNG, WebP, GIF and AVIF images are allowed".to_string())
+}
+
+fn can_see_unpublished(user: &Option<AuthUser>) -> bool {
+    user.as_ref().is_some_and(|user| user.can(Permission::ProjectsRead))
+}
+
+/// Unpublished projects look like they don't exist to the public.
+fn visible(project: Project, user: &Option<AuthUser>) -> Result<Project, AppError> {
+    if project.status == ProjectStatus::Published || can_see_unpublished(user) {
+        Ok(project)
+    } else {
+        Err(AppError::NotFound(format!("Project {} not found", project.id)))
+    }
+}
+
+/// Slugs may contain non-ASCII letters, which header values can't carry as is.
+fn percent_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
 }
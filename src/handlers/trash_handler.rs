@@ -0,0 +1,43 @@
+use actix_web::{get, web, HttpResponse};
+use crate::config::AppConfig;
+use crate::services::employee_service::EmployeeService;
+use crate::services::project_service::ProjectService;
+use crate::errors::AppError;
+use crate::models::api_response::ApiResponse;
+use crate::openapi::ErrorResponse;
+use crate::models::trash::Trash;
+use crate::extractors::auth::AuthUser;
+use crate::models::user::Permission;
+
+/// Trashed projects and employees, most recently deleted first.
+#[utoipa::path(
+    tag = "trash",
+    responses(
+        (status = 200, description = "Trashed items the caller may see", body = ApiResponse<Trash>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []), ("api_key" = []))
+)]
+#[get("/api/trash")]
+pub async fn get_trash(
+    project_svc: web::Data<ProjectService>,
+    employee_svc: web::Data<EmployeeService>,
+    config: web::Data<AppConfig>,
+    user: AuthUser
+) -> Result<HttpResponse, AppError> {
+    let can_projects = user.can(Permission::ProjectsRead);
+    let can_employees = user.can(Permission::EmployeesRead);
+    if !can_projects && !can_employees {
+        // Same error as the guards used everywhere else
+        user.require(Permission::ProjectsRead)?;
+    }
+
+    let trash = Trash {
+        retention_days: config.trash.retention_days,
+        projects: if can_projects { project_svc.trash().await? } else { Vec::new() },
+        employees: if can_employees { employee_svc.trash().await? } else { Vec::new() },
+    };
+    let count = trash.projects.len() + trash.employees.len();
+    Ok(HttpResponse::Ok().json(ApiResponse::success(format!("{} items in trash", count), Some(trash))))
+}
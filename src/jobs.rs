@@ -0,0 +1,70 @@
+//! Background tasks running next to the HTTP server.
+
+use actix_web::web;
+use chrono::Utc;
+use std::time::Duration;
+use crate::config::TrashSettings;
+use crate::rate_limit::RateLimiter;
+use crate::services::employee_service::EmployeeService;
+use crate::services::project_service::ProjectService;
+
+/// Publishes drafts whose `publish_at` has passed, checking every `interval`.
+pub fn spawn_scheduled_publisher(svc: web::Data<ProjectService>, interval: Duration) {
+    actix_web::rt::spawn(async move {
+        let mut ticker = actix_web::rt::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match svc.publish_scheduled().await {
+                Ok(published) => {
+                    for id in published {
+                        println!("📢 Published scheduled project {}", id);
+                    }
+                }
+                Err(e) => eprintln!("⚠️  Scheduled publishing failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Permanently removes trashed projects (with their stored images) and employees once they
+/// have been in the trash for `settings.retention_days`. Does nothing when retention is 0.
+pub fn spawn_trash_purger(
+    projects: web::Data<ProjectService>,
+    employees: web::Data<EmployeeService>,
+    settings: TrashSettings,
+) {
+    if settings.retention_days == 0 {
+        return;
+    }
+    actix_web::rt::spawn(async move {
+        let mut ticker = actix_web::rt::time::interval(settings.purge_interval);
+        loop {
+            ticker.tick().await;
+            let cutoff = Utc::now() - chrono::Duration::days(settings.retention_days as i64);
+            match projects.purge_trash(cutoff).await {
+                Ok(0) => {}
+                Ok(count) => println!("🗑️  Purged {} trashed projects", count),
+                Err(e) => eprintln!("⚠️  Purging trashed projects failed: {}", e),
+            }
+            match employees.purge_trash(cutoff).await {
+                Ok(0) => {}
+                Ok(count) => println!("🗑️  Purged {} trashed employees", count),
+                Err(e) => eprintln!("⚠️  Purging trashed employees failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Forgets idle rate limit buckets every ten minutes so the store doesn't grow with every
+/// client ever seen.
+pub fn spawn_rate_limit_purger(limiter: web::Data<RateLimiter>) {
+    actix_web::rt::spawn(async move {
+        let mut ticker = actix_web::rt::time::interval(Duration::from_secs(600));
+        loop {
+            ticker.tick().await;
+            if let Err(e) = limiter.purge().await {
+                eprintln!("⚠️  Purging rate limit buckets failed: {}", e);
+            }
+        }
+    });
+}
@@ -1,98 +1,254 @@
 mod config;
+mod cors;
+mod db_tls;
+mod errors;
 mod dtos;
 mod models;
 mod repositories;
 mod services;
 mod handlers;
+mod extractors;
+mod migrations;
+mod jobs;
+mod openapi;
+mod rate_limit;
+mod slug;
+mod validation;
 
-use actix_web::{App, HttpServer, web, middleware::Logger};
-use actix_cors::Cors;
+use actix_web::{App, HttpServer, web, middleware::{from_fn, Logger}};
 use actix_files as fs;
 use services::employee_service::EmployeeService;
 use services::project_service::ProjectService;
+use services::auth_service::AuthService;
+use services::category_service::CategoryService;
+use services::audit_service::AuditService;
+use services::api_key_service::ApiKeyService;
+use services::health_service::HealthService;
 use repositories::employee_postgres::EmployeePostgresRepo;
 use repositories::project_postgres::ProjectPostgresRepo;
+use repositories::user_postgres::UserPostgresRepo;
+use repositories::category_postgres::CategoryPostgresRepo;
+use repositories::audit_postgres::AuditPostgresRepo;
+use repositories::api_key_postgres::ApiKeyPostgresRepo;
 use handlers::employee_handler::*;
 use handlers::project_handler::*;
+use handlers::auth_handler::*;
+use handlers::category_handler::*;
+use handlers::trash_handler::*;
+use handlers::audit_handler::*;
+use handlers::api_key_handler::*;
+use handlers::docs_handler::*;
+use handlers::health_handler::*;
+use errors::AppError;
+use config::AppConfig;
+use rate_limit::{RateLimiter, RouteGroup};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
     env_logger::init();
 
+    // Configuration is loaded once; every problem is reported before giving up
+    let app_config = match AppConfig::load() {
+        Ok(app_config) => app_config,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
     // Setup PostgreSQL connection pool
-    let pg_pool = config::get_pg_pool();
+    let pg_pool = match app_config.database.create_pool() {
+        Ok(pg_pool) => pg_pool,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // `employee migrate [up | down [steps] | status]` manages the schema and exits
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("migrate") {
+        return migrations::run_cli(&pg_pool, &args[1..])
+            .await
+            .map_err(|e| std::io::Error::other(e.to_string()));
+    }
+
+    if app_config.database.auto_migrate {
+        match migrations::run_pending(&pg_pool).await {
+            Ok(applied) => {
+                for version in applied {
+                    println!("⬆️  Applied migration {:04}", version);
+                }
+            }
+            Err(e) => return Err(std::io::Error::other(format!("Database migration failed: {}", e))),
+        }
+    }
     
+    // Audit service, shared by every service that records changes
+    let audit_svc = std::sync::Arc::new(AuditService {
+        pg_repo: AuditPostgresRepo { pool: pg_pool.clone() },
+    });
+
     // Employee service
     let employee_repo = EmployeePostgresRepo { pool: pg_pool.clone() };
-    let employee_svc = web::Data::new(EmployeeService { pg_repo: employee_repo });
+    let employee_svc = web::Data::new(EmployeeService {
+        pg_repo: employee_repo,
+        audit: audit_svc.clone(),
+    });
     
     // Project service
+    let category_svc = web::Data::new(CategoryService {
+        pg_repo: CategoryPostgresRepo { pool: pg_pool.clone() },
+    });
+
     let project_repo = ProjectPostgresRepo { pool: pg_pool.clone() };
-    let project_svc = web::Data::new(ProjectService { pg_repo: project_repo });
+    let image_storage = app_config.storage.build().map_err(std::io::Error::other)?;
+    let project_svc = web::Data::new(ProjectService {
+        pg_repo: project_repo,
+        storage: image_storage.clone(),
+        pipeline: std::sync::Arc::new(app_config.images.clone()),
+        audit: audit_svc.clone(),
+    });
+    // API keys of machine clients, checked by the same `AuthUser` guard as login tokens
+    let api_key_svc = web::Data::new(ApiKeyService {
+        pg_repo: ApiKeyPostgresRepo { pool: pg_pool.clone() },
+        audit: audit_svc.clone(),
+    });
+    let audit_svc = web::Data::from(audit_svc);
+
+    // Auth service
+    let user_repo = UserPostgresRepo { pool: pg_pool.clone() };
+    let auth_svc = web::Data::new(AuthService::new(
+        user_repo,
+        &app_config.auth.jwt_secret,
+        app_config.auth.token_ttl_secs,
+    ));
+    if let Some((email, password)) = &app_config.auth.bootstrap_admin {
+        if let Err(e) = auth_svc.ensure_admin(email, password).await {
+            eprintln!("⚠️  Failed to create admin user: {}", e);
+        }
+    }
+
+    // Readiness probe
+    let health_svc = web::Data::new(HealthService {
+        pool: pg_pool.clone(),
+        storage: image_storage.clone(),
+    });
+
+    // Rate limits, kept in memory or in Postgres
+    let rate_limiter = web::Data::new(RateLimiter {
+        store: app_config.rate_limit.build_store(&pg_pool),
+        config: app_config.rate_limit.clone(),
+    });
+
+    jobs::spawn_scheduled_publisher(project_svc.clone(), app_config.publish_interval);
+    jobs::spawn_trash_purger(project_svc.clone(), employee_svc.clone(), app_config.trash);
+    jobs::spawn_rate_limit_purger(rate_limiter.clone());
 
-    // Create uploads directory if not exists
-    std::fs::create_dir_all("./uploads").unwrap_or_default();
+    let bind = (app_config.server.host.clone(), app_config.server.port);
+    let app_config = web::Data::new(app_config);
 
-    println!("🚀 Server starting on http://127.0.0.1:8080");
-    println!("📊 Database: PostgreSQL");
-    println!("🌐 CORS enabled for frontend");
-    println!("📁 Static files served from /uploads");
+    println!("🚀 Server starting on http://{}:{} ({} profile)", bind.0, bind.1, app_config.profile);
+    println!(
+        "📊 Database: PostgreSQL (sslmode={}, pool of {})",
+        app_config.database.ssl_mode, app_config.database.max_size
+    );
+    let origins: Vec<String> = app_config.cors.allowed_origins.iter().map(ToString::to_string).collect();
+    println!("🌐 CORS origins: {}", if origins.is_empty() { "none".to_string() } else { origins.join(", ") });
+    println!("📁 Images stored with {} storage", image_storage.name());
+    let limits: Vec<String> = RouteGroup::ALL
+        .iter()
+        .filter_map(|group| {
+            let limit = app_config.rate_limit.limit(*group)?;
+            Some(format!("{} {}/min (burst {})", group, limit.per_minute, limit.burst))
+        })
+        .collect();
+    println!(
+        "🚦 Rate limits: {} ({} store)",
+        if limits.is_empty() { "none".to_string() } else { limits.join(", ") },
+        rate_limiter.store.name()
+    );
 
     HttpServer::new(move || {
-        // Konfigurasi CORS - Support both local and production
-        let cors = Cors::default()
-            // Local development
-            .allowed_origin("http://localhost:3001")
-            .allowed_origin("http://localhost:3002")
-            .allowed_origin("http://localhost:3000")
-            .allowed_origin("http://127.0.0.1:3000")
-            .allowed_origin("http://localhost:5173")
-            .allowed_origin("http://127.0.0.1:5173")
-            // Production - Allow all Vercel domains
-            .allowed_origin_fn(|origin, _req_head| {
-                origin.as_bytes().ends_with(b".vercel.app") ||
-                origin.as_bytes().ends_with(b"vercel.app")
-            })
-            .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
-            .allowed_headers(vec![
-                "accept",
-                "authorization", 
-                "content-type",
-                "user-agent",
-                "x-requested-with"
-            ])
-            .max_age(3600);
+        // Origins, methods and headers come from `AppConfig.cors`
+        let cors = cors::middleware(&app_config.cors);
 
         App::new()
+            // Innermost, so a `429` still gets the CORS headers
+            .wrap(from_fn(rate_limit::middleware))
             .wrap(cors)
             .wrap(Logger::default())
+            // Malformed JSON, paths and query strings use the same error envelope
+            .app_data(web::JsonConfig::default().error_handler(|err, _| AppError::BadRequest(err.to_string()).into()))
+            .app_data(web::PathConfig::default().error_handler(|err, _| AppError::BadRequest(err.to_string()).into()))
+            .app_data(web::QueryConfig::default().error_handler(|err, _| AppError::BadRequest(err.to_string()).into()))
             .app_data(employee_svc.clone())
             .app_data(project_svc.clone())
+            .app_data(category_svc.clone())
+            .app_data(app_config.clone())
+            .app_data(audit_svc.clone())
+            .app_data(auth_svc.clone())
+            .app_data(api_key_svc.clone())
+            .app_data(health_svc.clone())
+            .app_data(rate_limiter.clone())
+            // Health probes
+            .service(healthz)
+            .service(readyz)
+            .service(get_version)
+            // Auth endpoints
+            .service(login)
+            .service(me)
+            .service(create_user)
+            .service(create_api_key)
+            .service(get_api_keys)
+            .service(revoke_api_key)
             // Employee endpoints
             .service(add_employee)
             .service(get_all_employees)
             .service(get_employee_by_id)
             .service(update_employee)
             .service(delete_employee)
+            .service(restore_employee)
             // Project endpoints
             .service(add_project)
             .service(upload_project_image)
             .service(create_project_with_upload)
             .service(get_all_projects)
             .service(get_project_by_id)
+            .service(get_project_by_slug)
+            .service(get_tag_cloud)
             .service(update_project)
             .service(delete_project)
+            .service(restore_project)
+            // Project image endpoints
+            .service(add_project_image)
+            .service(upload_and_add_project_image)
+            .service(reorder_project_images)
+            .service(update_project_image)
+            .service(delete_project_image)
+            // Project revision endpoints
+            .service(get_project_revisions)
+            .service(get_project_revision)
+            .service(diff_project_revision)
+            .service(restore_project_revision)
+            // Category endpoints
+            .service(add_category)
+            .service(get_all_categories)
+            .service(get_category_by_id)
+            .service(update_category)
+            .service(delete_category)
+            // Trash
+            .service(get_trash)
+            // Audit log
+            .service(get_audit_log)
+            // API documentation
+            .service(openapi_json)
+            .service(api_docs)
             // Serve static files (uploaded images)
-            .service(fs::Files::new("/uploads", "./uploads").show_files_listing())
+            .service(fs::Files::new("/uploads", &app_config.storage.upload_dir).show_files_listing())
     })
-    .bind((
-        "0.0.0.0", 
-        std::env::var("PORT")
-            .unwrap_or_else(|_| "8080".to_string())
-            .parse()
-            .expect("PORT must be a number")
-    ))?
+    .bind(bind)?
     .run()
     .await
 }
@@ -0,0 +1,199 @@
+//! Versioned schema migrations embedded in the binary.
+//!
+//! Each migration is a pair of `migrations/NNNN_name.{up,down}.sql` files. Applied versions
+//! are tracked in the `schema_migrations` table; every migration runs in its own transaction.
+
+use deadpool_postgres::{Pool, Transaction};
+use futures_util::future::LocalBoxFuture;
+use crate::errors::AppError;
+use crate::repositories::project_postgres;
+
+/// Data migration written in Rust, for rules SQL can't express the same way (e.g. `slugify`).
+pub type Backfill = for<'a> fn(&'a Transaction<'a>) -> LocalBoxFuture<'a, Result<(), AppError>>;
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    /// Runs right after `up`, in the same transaction.
+    pub backfill: Option<Backfill>,
+}
+
+macro_rules! migration {
+    ($version:literal, $name:literal) => {
+        migration!($version, $name, None)
+    };
+    ($version:literal, $name:literal, backfill = $backfill:path) => {
+        migration!($version, $name, Some($backfill as Backfill))
+    };
+    ($version:literal, $name:literal, $backfill:expr) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!("../migrations/", $name, ".up.sql")),
+            down: include_str!(concat!("../migrations/", $name, ".down.sql")),
+            backfill: $backfill,
+        }
+    };
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_create_employees"),
+    migration!(2, "0002_create_projects"),
+    migration!(3, "0003_create_users"),
+    migration!(4, "0004_full_text_search"),
+    migration!(5, "0005_image_renditions"),
+    migration!(6, "0006_project_images"),
+    migration!(7, "0007_timestamptz"),
+    migration!(8, "0008_categories"),
+    migration!(9, "0009_tags"),
+    migration!(10, "0010_project_slugs", backfill = backfill_project_slugs),
+    migration!(11, "0011_project_status"),
+    migration!(12, "0012_soft_delete"),
+    migration!(13, "0013_audit_log"),
+    migration!(14, "0014_project_revisions"),
+    migration!(15, "0015_rate_limit_buckets"),
+    migration!(16, "0016_api_keys"),
+];
+
+fn backfill_project_slugs<'a>(tx: &'a Transaction<'a>) -> LocalBoxFuture<'a, Result<(), AppError>> {
+    Box::pin(project_postgres::backfill_slugs(&**tx))
+}
+
+/// Arbitrary key for `pg_advisory_lock` so concurrent instances don't migrate at the same time.
+const LOCK_KEY: i64 = 0x706f_7274_666f_6c69;
+
+pub struct MigrationStatus {
+    pub name: &'static str,
+    pub applied: bool,
+}
+
+async fn applied_versions(client: &deadpool_postgres::Client) -> Result<Vec<i64>, AppError> {
+    client
+        .batch_execute(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+                version BIGINT PRIMARY KEY,
+                name TEXT NOT NULL,
+                applied_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            )",
+        )
+        .await?;
+    let rows = client
+        .query("SELECT version FROM schema_migrations ORDER BY version", &[])
+        .await?;
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+/// Applies every pending migration in version order and returns the versions applied.
+pub async fn run_pending(pool: &Pool) -> Result<Vec<i64>, AppError> {
+    let mut client = pool.get().await?;
+    client.execute("SELECT pg_advisory_lock($1)", &[&LOCK_KEY]).await?;
+
+    let result = async {
+        let applied = applied_versions(&client).await?;
+        let mut newly_applied = Vec::new();
+        for migration in MIGRATIONS.iter().filter(|m| !applied.contains(&m.version)) {
+            let tx = client.transaction().await?;
+            tx.batch_execute(migration.up).await.map_err(|e| {
+                AppError::Storage(format!("Migration {} failed: {}", migration.name, e))
+            })?;
+            if let Some(backfill) = migration.backfill {
+                backfill(&tx).await?;
+            }
+            tx.execute(
+                "INSERT INTO schema_migrations (version, name) VALUES ($1, $2)",
+                &[&migration.version, &migration.name],
+            )
+            .await?;
+            tx.commit().await?;
+            newly_applied.push(migration.version);
+        }
+        Ok(newly_applied)
+    }
+    .await;
+
+    client.execute("SELECT pg_advisory_unlock($1)", &[&LOCK_KEY]).await?;
+    result
+}
+
+/// Reverts the last `steps` applied migrations, newest first, and returns the versions reverted.
+pub async fn rollback(pool: &Pool, steps: usize) -> Result<Vec<i64>, AppError> {
+    let mut client = pool.get().await?;
+    client.execute("SELECT pg_advisory_lock($1)", &[&LOCK_KEY]).await?;
+
+    let result = async {
+        let applied = applied_versions(&client).await?;
+        let mut reverted = Vec::new();
+        for version in applied.iter().rev().take(steps) {
+            let migration = MIGRATIONS
+                .iter()
+                .find(|m| m.version == *version)
+                .ok_or_else(|| AppError::Internal(format!("Applied migration {} is not known to this binary", version)))?;
+            let tx = client.transaction().await?;
+            tx.batch_execute(migration.down).await.map_err(|e| {
+                AppError::Storage(format!("Rollback of {} failed: {}", migration.name, e))
+            })?;
+            tx.execute("DELETE FROM schema_migrations WHERE version = $1", &[&migration.version])
+                .await?;
+            tx.commit().await?;
+            reverted.push(migration.version);
+        }
+        Ok(reverted)
+    }
+    .await;
+
+    client.execute("SELECT pg_advisory_unlock($1)", &[&LOCK_KEY]).await?;
+    result
+}
+
+pub async fn status(pool: &Pool) -> Result<Vec<MigrationStatus>, AppError> {
+    let client = pool.get().await?;
+    let applied = applied_versions(&client).await?;
+    Ok(MIGRATIONS
+        .iter()
+        .map(|m| MigrationStatus {
+            name: m.name,
+            applied: applied.contains(&m.version),
+        })
+        .collect())
+}
+
+/// Entry point for `employee migrate [up | down [steps] | status]`.
+pub async fn run_cli(pool: &Pool, args: &[String]) -> Result<(), AppError> {
+    match args.first().map(String::as_str).unwrap_or("up") {
+        "up" => {
+            let applied = run_pending(pool).await?;
+            if applied.is_empty() {
+                println!("✅ Database schema is up to date");
+            }
+            for version in applied {
+                println!("⬆️  Applied migration {:04}", version);
+            }
+        }
+        "down" => {
+            let steps = match args.get(1) {
+                Some(steps) => steps
+                    .parse()
+                    .map_err(|_| AppError::BadRequest(format!("Invalid number of steps: {}", steps)))?,
+                None => 1,
+            };
+            for version in rollback(pool, steps).await? {
+                println!("⬇️  Reverted migration {:04}", version);
+            }
+        }
+        "status" => {
+            for m in status(pool).await? {
+                let mark = if m.applied { "applied" } else { "pending" };
+                println!("{:<40} {}", m.name, mark);
+            }
+        }
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "Unknown migrate command '{}'. Use: migrate [up | down [steps] | status]",
+                other
+            )));
+        }
+    }
+    Ok(())
+}
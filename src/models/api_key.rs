@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+use utoipa::ToSchema;
+use crate::models::user::Permission;
+
+/// What an API key may do. Managing users and keys is never granted to a key.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+pub enum Scope {
+    /// See unpublished projects and trashed ones.
+    #[serde(rename = "projects:read")]
+    ProjectsRead,
+    /// Create, edit and delete projects, their images and categories.
+    #[serde(rename = "projects:write")]
+    ProjectsWrite,
+    /// See trashed employees.
+    #[serde(rename = "employees:read")]
+    EmployeesRead,
+    #[serde(rename = "employees:write")]
+    EmployeesWrite,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::ProjectsRead => "projects:read",
+            Scope::ProjectsWrite => "projects:write",
+            Scope::EmployeesRead => "employees:read",
+            Scope::EmployeesWrite => "employees:write",
+        }
+    }
+
+    pub fn permission(&self) -> Permission {
+        match self {
+            Scope::ProjectsRead => Permission::ProjectsRead,
+            Scope::ProjectsWrite => Permission::ProjectsWrite,
+            Scope::EmployeesRead => Permission::EmployeesRead,
+            Scope::EmployeesWrite => Permission::EmployeesWrite,
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "projects:read" => Ok(Scope::ProjectsRead),
+            "projects:write" => Ok(Scope::ProjectsWrite),
+            "employees:read" => Ok(Scope::EmployeesRead),
+            "employees:write" => Ok(Scope::EmployeesWrite),
+            other => Err(format!("Unknown scope: {}", other)),
+        }
+    }
+}
+
+/// A key for machine clients, sent in the `X-Api-Key` header. Only its hash is stored.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    /// Start of the key, to tell keys apart.
+    #[schema(example = "pk_3f9a1c2e")]
+    pub prefix: String,
+    pub scopes: Vec<Scope>,
+    /// Changes made with the key are attributed to this user, and the key can't do more
+    /// than the user's role allows.
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Updated at most once a minute.
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,55 @@
+use serde::Serialize;
+use crate::models::pagination::PaginationMeta;
+use crate::validation::FieldErrors;
+use utoipa::ToSchema;
+
+/// JSON envelope returned by every endpoint.
+#[derive(Serialize, ToSchema)]
+pub struct ApiResponse<T> {
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub message: String,
+    pub data: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<PaginationMeta>,
+    /// Per-field messages of a `validation_error`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<BTreeMap<String, Vec<String>>>)]
+    pub errors: Option<FieldErrors>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn success(message: impl Into<String>, data: Option<T>) -> Self {
+        Self {
+            status: "success".to_string(),
+            code: None,
+            message: message.into(),
+            data,
+            meta: None,
+            errors: None,
+        }
+    }
+
+    pub fn paginated(message: impl Into<String>, data: T, meta: PaginationMeta) -> Self {
+        Self {
+            status: "success".to_string(),
+            code: None,
+            message: message.into(),
+            data: Some(data),
+            meta: Some(meta),
+            errors: None,
+        }
+    }
+
+    pub fn error(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            status: "error".to_string(),
+            code: Some(code.to_string()),
+            message: message.into(),
+            data: None,
+            meta: None,
+            errors: None,
+        }
+    }
+}
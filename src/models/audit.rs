@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    /// Moved to the trash.
+    Delete,
+    Restore,
+    /// Removed from the trash for good.
+    Purge,
+    /// Scheduled draft published by the server.
+    Publish,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Purge => "purge",
+            AuditAction::Publish => "publish",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "create" => Ok(AuditAction::Create),
+            "update" => Ok(AuditAction::Update),
+            "delete" => Ok(AuditAction::Delete),
+            "restore" => Ok(AuditAction::Restore),
+            "purge" => Ok(AuditAction::Purge),
+            "publish" => Ok(AuditAction::Publish),
+            other => Err(format!("Unknown audit action: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditEntity {
+    Project,
+    Employee,
+    #[serde(rename = "api_key")]
+    ApiKey,
+}
+
+impl AuditEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEntity::Project => "project",
+            AuditEntity::Employee => "employee",
+            AuditEntity::ApiKey => "api_key",
+        }
+    }
+}
+
+impl fmt::Display for AuditEntity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditEntity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "project" => Ok(AuditEntity::Project),
+            "employee" => Ok(AuditEntity::Employee),
+            "api_key" => Ok(AuditEntity::ApiKey),
+            other => Err(format!("Unknown audit entity: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    /// `None` for changes made by background jobs or by since deleted users.
+    pub actor_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub action: AuditAction,
+    pub entity: AuditEntity,
+    pub entity_id: Uuid,
+    /// `{ "<field>": { "before": ..., "after": ... } }` for every changed field.
+    #[schema(value_type = Object)]
+    pub changes: Value,
+    pub created_at: DateTime<Utc>,
+}
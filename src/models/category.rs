@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct Category {
+    pub id: Uuid,
+    /// Value stored in `Project.category` and used by `GET /api/projects?category=`.
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Icon name or URL, interpreted by the frontend.
+    pub icon: Option<String>,
+    pub sort_order: i32,
+    pub project_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
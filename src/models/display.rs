@@ -0,0 +1,64 @@
+use chrono::{DateTime, Locale, Utc};
+use chrono_tz::Tz;
+use crate::errors::AppError;
+use crate::models::search::SearchHit;
+
+/// e.g. `18 Oktober 2026 12:29 WIB` for `tz=Asia/Jakarta&locale=id_ID`.
+const DISPLAY_PATTERN: &str = "%-d %B %Y %H:%M %Z";
+
+/// Time zone and locale used for the optional `*_display` timestamp fields.
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayFormat {
+    tz: Tz,
+    locale: Locale,
+}
+
+impl DisplayFormat {
+    /// `None` when neither `tz` nor `locale` was requested, which leaves the display fields out.
+    /// A missing time zone defaults to UTC and a missing locale to `en_US`.
+    pub fn from_query(tz: Option<&str>, locale: Option<&str>) -> Result<Option<Self>, AppError> {
+        if tz.is_none() && locale.is_none() {
+            return Ok(None);
+        }
+        let tz = match tz {
+            Some(name) => name
+                .parse::<Tz>()
+                .map_err(|_| AppError::BadRequest(format!("Unknown time zone '{}'", name)))?,
+            None => Tz::UTC,
+        };
+        let locale = match locale {
+            Some(name) => Locale::try_from(name.replace('-', "_").as_str())
+                .map_err(|_| AppError::BadRequest(format!("Unknown locale '{}'", name)))?,
+            None => Locale::en_US,
+        };
+        Ok(Some(Self { tz, locale }))
+    }
+
+    pub fn format(&self, at: DateTime<Utc>) -> String {
+        at.with_timezone(&self.tz)
+            .format_localized(DISPLAY_PATTERN, self.locale)
+            .to_string()
+    }
+}
+
+/// Models that carry `*_display` variants of their timestamps.
+pub trait Localize {
+    fn localize(&mut self, format: &DisplayFormat);
+}
+
+impl<T: Localize> Localize for SearchHit<T> {
+    fn localize(&mut self, format: &DisplayFormat) {
+        self.item.localize(format);
+    }
+}
+
+/// Fills the display fields of every item when a format was requested.
+pub fn localize_all<'a, T, I>(items: I, format: Option<DisplayFormat>)
+where
+    T: Localize + 'a,
+    I: IntoIterator<Item = &'a mut T>,
+{
+    if let Some(format) = format {
+        items.into_iter().for_each(|item| item.localize(&format));
+    }
+}
@@ -1,17 +1,31 @@
+use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
+use crate::models::display::{DisplayFormat, Localize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Employee {
     pub id: Uuid,
     pub name: String,
     pub position: String,
     pub email: String,
+    /// RFC 3339, always UTC.
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Only present when a `tz` or `locale` query parameter was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at_display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at_display: Option<String>,
+    /// Set while the employee is in the trash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Serialize)]
-pub struct ApiResponse<T> {
-    pub status: String,      
-    pub message: String,      
-    pub data: Option<T>,      
+impl Localize for Employee {
+    fn localize(&mut self, format: &DisplayFormat) {
+        self.created_at_display = Some(format.format(self.created_at));
+        self.updated_at_display = Some(format.format(self.updated_at));
+    }
 }
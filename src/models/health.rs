@@ -0,0 +1,45 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    Error,
+}
+
+/// Body of `GET /healthz`: the process is up and serving requests.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Liveness {
+    pub status: HealthStatus,
+}
+
+/// Body of `GET /readyz`; `status` is `error` as soon as one check fails.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Readiness {
+    pub status: HealthStatus,
+    pub database: Check,
+    pub storage: Check,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Check {
+    pub status: HealthStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// Storage backend name, for the storage check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Body of `GET /version`, embedded at build time by `build.rs`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    /// Commit the binary was built from, `unknown` outside a git checkout.
+    pub git_sha: &'static str,
+    /// RFC 3339, UTC.
+    pub build_time: &'static str,
+}
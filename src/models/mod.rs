@@ -1,2 +1,14 @@
+pub mod api_key;
+pub mod api_response;
+pub mod audit;
+pub mod category;
+pub mod display;
 pub mod employee;
-pub mod project;
\ No newline at end of file
+pub mod health;
+pub mod pagination;
+pub mod project;
+pub mod revision;
+pub mod search;
+pub mod tag;
+pub mod trash;
+pub mod user;
@@ -0,0 +1,33 @@
+use serde::Serialize;
+use crate::dtos::pagination_dto::PageRequest;
+use utoipa::ToSchema;
+
+/// One page of rows plus the total number of rows matching the query.
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaginationMeta {
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub has_more: bool,
+    /// `offset` of the next page; absent on the last page.
+    pub next_offset: Option<i64>,
+}
+
+impl<T> Page<T> {
+    pub fn meta(&self, request: &PageRequest) -> PaginationMeta {
+        let next_offset = request.offset + self.items.len() as i64;
+        let has_more = next_offset < self.total;
+        PaginationMeta {
+            total: self.total,
+            page: request.page(),
+            per_page: request.limit,
+            has_more,
+            next_offset: has_more.then_some(next_offset),
+        }
+    }
+}
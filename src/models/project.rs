@@ -1,20 +1,124 @@
+use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
+use crate::models::display::{DisplayFormat, Localize};
+use crate::models::tag::Tag;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Project {
     pub id: Uuid,
     pub name: String,
+    /// Unique, URL-friendly form of the name; changes when the project is renamed.
+    pub slug: String,
     pub description: String,
-    pub images: Vec<String>,  // Array of image URLs
+    /// Ordered by `position`.
+    pub images: Vec<ProjectImage>,
     pub category: String,
-    pub created_at: Option<String>,
-    pub updated_at: Option<String>,
+    /// Tech stack, sorted by name.
+    pub tags: Vec<Tag>,
+    /// Only published projects are visible without authentication.
+    pub status: ProjectStatus,
+    /// When a draft is published automatically.
+    pub publish_at: Option<DateTime<Utc>>,
+    /// RFC 3339, always UTC.
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Only present when a `tz` or `locale` query parameter was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at_display: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at_display: Option<String>,
+    /// Set while the project is in the trash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Serialize)]
-pub struct ApiResponse<T> {
-    pub status: String,
-    pub message: String,
-    pub data: Option<T>,
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectStatus {
+    Draft,
+    Published,
+    Archived,
 }
+
+impl ProjectStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectStatus::Draft => "draft",
+            ProjectStatus::Published => "published",
+            ProjectStatus::Archived => "archived",
+        }
+    }
+}
+
+impl fmt::Display for ProjectStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProjectStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "draft" => Ok(ProjectStatus::Draft),
+            "published" => Ok(ProjectStatus::Published),
+            "archived" => Ok(ProjectStatus::Archived),
+            other => Err(format!("Unknown project status: {}", other)),
+        }
+    }
+}
+
+impl Localize for Project {
+    fn localize(&mut self, format: &DisplayFormat) {
+        self.created_at_display = Some(format.format(self.created_at));
+        self.updated_at_display = Some(format.format(self.updated_at));
+    }
+}
+
+/// Result of looking a project up by slug.
+pub enum SlugLookup {
+    Current(Box<Project>),
+    /// The slug used to belong to this project, which now lives at its current slug.
+    Moved(Box<Project>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct ProjectImage {
+    pub id: Uuid,
+    pub url: String,
+    pub alt: Option<String>,
+    pub caption: Option<String>,
+    pub position: i32,
+    pub is_cover: bool,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub blurhash: Option<String>,
+    /// Generated renditions for building `srcset`s.
+    #[schema(value_type = BTreeMap<String, Rendition>)]
+    pub renditions: RenditionSet,
+}
+
+impl ProjectImage {
+    /// Every stored file behind this image: the image itself plus its renditions.
+    pub fn asset_urls(&self) -> Vec<String> {
+        let mut urls = vec![self.url.clone()];
+        urls.extend(self.renditions.values().map(|r| r.url.clone()).filter(|u| *u != self.url));
+        urls
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct Rendition {
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// Rendition name (`original`, `medium`, `thumbnail`, ...) -> stored file.
+pub type RenditionSet = BTreeMap<String, Rendition>;
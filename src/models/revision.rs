@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use uuid::Uuid;
+use crate::models::project::{Project, ProjectImage};
+use crate::models::tag::Tag;
+use utoipa::ToSchema;
+
+/// The editable content of a project, as stored in a revision. Status, slug and
+/// timestamps are not part of it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct ProjectContent {
+    pub name: String,
+    pub description: String,
+    pub category: String,
+    pub tags: Vec<Tag>,
+    pub images: Vec<ProjectImage>,
+}
+
+impl From<&Project> for ProjectContent {
+    fn from(project: &Project) -> Self {
+        ProjectContent {
+            name: project.name.clone(),
+            description: project.description.clone(),
+            category: project.category.clone(),
+            tags: project.tags.clone(),
+            images: project.images.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct ProjectRevision {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// 1 for the oldest revision of the project.
+    pub revision: i32,
+    /// The project as it was before the change made by `actor_id` at `created_at`.
+    pub content: ProjectContent,
+    pub actor_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevisionDiff {
+    pub revision: i32,
+    /// Revision compared against; `None` for the current content of the project.
+    pub against: Option<i32>,
+    /// `{ "<field>": { "before": ..., "after": ... } }` going from `revision` to `against`.
+    #[schema(value_type = Object)]
+    pub changes: Value,
+}
+
+/// A project as it was before and after an edit; the edit itself already recorded the
+/// revision, this is what goes into the audit entry.
+#[derive(Debug)]
+pub struct ProjectChange {
+    pub before: Project,
+    pub after: Project,
+}
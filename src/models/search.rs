@@ -0,0 +1,27 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// `ts_headline` options shared by every search query.
+const HEADLINE_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>, MaxWords=35, MinWords=15, MaxFragments=2";
+
+/// SQL for the `ts_headline` of `column` against the `tsq` query. The text is HTML-escaped
+/// first, so the `<mark>` tags are the only markup a fragment can contain.
+pub fn headline_sql(column: &str) -> String {
+    let escaped = [("&", "&amp;"), ("<", "&lt;"), (">", "&gt;"), ("\"", "&quot;"), ("'", "&#39;")]
+        .iter()
+        .fold(column.to_string(), |sql, (from, to)| {
+            format!("replace({}, '{}', '{}')", sql, from.replace('\'', "''"), to)
+        });
+    format!("ts_headline('simple', {}, tsq, '{}')", escaped, HEADLINE_OPTIONS)
+}
+
+/// A full-text search match: the row itself plus its relevance and highlighted fragments.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SearchHit<T> {
+    #[serde(flatten)]
+    pub item: T,
+    pub rank: f32,
+    /// Field name -> fragment with matches wrapped in `<mark>` tags.
+    pub highlights: BTreeMap<&'static str, String>,
+}
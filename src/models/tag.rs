@@ -0,0 +1,18 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+
+/// A technology or topic attached to projects, e.g. `{ "slug": "rust", "name": "Rust" }`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct Tag {
+    /// Used by `GET /api/projects?tags=`.
+    pub slug: String,
+    pub name: String,
+}
+
+/// A tag cloud entry.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TagCount {
+    #[serde(flatten)]
+    pub tag: Tag,
+    pub project_count: i64,
+}
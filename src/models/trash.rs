@@ -0,0 +1,13 @@
+use serde::Serialize;
+use crate::models::employee::Employee;
+use crate::models::project::Project;
+use utoipa::ToSchema;
+
+/// Contents of `GET /api/trash`. Each list is only filled when the caller may edit that kind of item.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Trash {
+    /// Days after `deleted_at` an item is purged for good; `0` means never.
+    pub retention_days: u32,
+    pub projects: Vec<Project>,
+    pub employees: Vec<Employee>,
+}
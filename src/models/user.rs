@@ -0,0 +1,71 @@
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Admin,
+    Editor,
+    Viewer,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Editor => "editor",
+            Role::Viewer => "viewer",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "admin" => Ok(Role::Admin),
+            "editor" => Ok(Role::Editor),
+            "viewer" => Ok(Role::Viewer),
+            other => Err(format!("Unknown role: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct User {
+    pub id: Uuid,
+    pub email: String,
+    pub role: Role,
+}
+
+/// Actions that require authentication. Roles are mapped onto these in `Role::grants`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// See unpublished and trashed projects.
+    ProjectsRead,
+    ProjectsWrite,
+    /// See trashed employees.
+    EmployeesRead,
+    EmployeesWrite,
+    UsersManage,
+}
+
+impl Role {
+    pub fn grants(&self, permission: Permission) -> bool {
+        match self {
+            Role::Admin => true,
+            Role::Editor => !matches!(permission, Permission::UsersManage),
+            Role::Viewer => false,
+        }
+    }
+}
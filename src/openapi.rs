@@ -0,0 +1,172 @@
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+use crate::dtos::employee_dto::EmployeeSortField;
+use crate::dtos::pagination_dto::SortOrder;
+use crate::dtos::project_dto::{ProjectSortField, TagMatch};
+use crate::handlers::{api_key_handler, audit_handler, auth_handler, category_handler, docs_handler, employee_handler, health_handler, project_handler, trash_handler};
+use crate::validation::FieldErrors;
+
+/// OpenAPI 3 description of the API, served at `GET /api/openapi.json`.
+///
+/// Every handler documents itself with `#[utoipa::path]`; new endpoints must be listed here too.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Portfolio API",
+        description = "Projects, employees and categories of the portfolio site. Every response uses the \
+                       `status` / `message` / `data` envelope; errors carry a stable `code`. Requests are \
+                       rate limited per route group; `429 rate_limited` comes with `Retry-After`."
+    ),
+    paths(
+        auth_handler::login,
+        auth_handler::me,
+        auth_handler::create_user,
+        api_key_handler::create_api_key,
+        api_key_handler::get_api_keys,
+        api_key_handler::revoke_api_key,
+        employee_handler::add_employee,
+        employee_handler::get_all_employees,
+        employee_handler::get_employee_by_id,
+        employee_handler::update_employee,
+        employee_handler::delete_employee,
+        employee_handler::restore_employee,
+        project_handler::add_project,
+        project_handler::upload_project_image,
+        project_handler::create_project_with_upload,
+        project_handler::get_all_projects,
+        project_handler::get_project_by_id,
+        project_handler::get_project_by_slug,
+        project_handler::get_tag_cloud,
+        project_handler::update_project,
+        project_handler::delete_project,
+        project_handler::restore_project,
+        project_handler::add_project_image,
+        project_handler::upload_and_add_project_image,
+        project_handler::reorder_project_images,
+        project_handler::update_project_image,
+        project_handler::delete_project_image,
+        project_handler::get_project_revisions,
+        project_handler::get_project_revision,
+        project_handler::diff_project_revision,
+        project_handler::restore_project_revision,
+        category_handler::add_category,
+        category_handler::get_all_categories,
+        category_handler::get_category_by_id,
+        category_handler::update_category,
+        category_handler::delete_category,
+        trash_handler::get_trash,
+        audit_handler::get_audit_log,
+        docs_handler::openapi_json,
+        health_handler::healthz,
+        health_handler::readyz,
+        health_handler::get_version,
+    ),
+    // Query parameter enums aren't collected from `params(...)` automatically
+    components(schemas(ProjectSortField, TagMatch, EmployeeSortField, SortOrder)),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "auth", description = "Login, users and API keys"),
+        (name = "employees"),
+        (name = "projects", description = "Projects, their images, tags and revisions"),
+        (name = "categories"),
+        (name = "trash", description = "Soft-deleted projects and employees"),
+        (name = "audit"),
+        (name = "docs"),
+        (name = "health", description = "Probes for the deployment platform"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the `bearer_auth` and `api_key` schemes referenced by the `security` of
+/// protected endpoints.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some("Token returned by `POST /api/auth/login`"))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                "X-Api-Key",
+                "Key created with `POST /api/auth/api-keys`; limited to its scopes",
+            ))),
+        );
+    }
+}
+
+// The types below only describe bodies that the handlers build by hand; they are never constructed.
+
+/// Envelope of every error. `code` is stable and meant for clients to match on.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct ErrorResponse {
+    /// Always `error`.
+    status: String,
+    #[schema(example = "not_found")]
+    code: String,
+    message: String,
+    /// Always `null`.
+    data: Option<String>,
+    /// Per-field messages of a `validation_error`.
+    #[schema(value_type = Option<BTreeMap<String, Vec<String>>>)]
+    errors: Option<FieldErrors>,
+}
+
+/// Envelope of a successful response without data.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct MessageResponse {
+    /// Always `success`.
+    status: String,
+    message: String,
+    /// Always `null`.
+    data: Option<String>,
+}
+
+/// `multipart/form-data` body of `POST /api/projects/upload`; only the first file is stored.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct ImageUploadForm {
+    #[schema(value_type = String, format = Binary)]
+    file: Vec<u8>,
+}
+
+/// `multipart/form-data` body of `POST /api/projects/create-with-upload`.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct CreateProjectForm {
+    name: String,
+    description: String,
+    category: String,
+    /// Comma separated, or the field repeated (also accepted as `tags[]`).
+    tags: Option<String>,
+    /// `draft`, `published` or `archived`.
+    status: Option<String>,
+    /// RFC 3339 timestamp.
+    publish_at: Option<String>,
+    /// One or more images, also accepted as `file`, `image` or `files`.
+    #[schema(value_type = Vec<String>, format = Binary)]
+    images: Vec<Vec<u8>>,
+}
+
+/// `multipart/form-data` body of `POST /api/projects/{id}/images/upload`.
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct ProjectImageUploadForm {
+    #[schema(value_type = String, format = Binary)]
+    file: Vec<u8>,
+    alt: Option<String>,
+    caption: Option<String>,
+    /// `true` makes the image the cover.
+    is_cover: Option<bool>,
+}
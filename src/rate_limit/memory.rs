@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::errors::AppError;
+use super::{Limit, RateLimitStore};
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Buckets in the process memory: fast, but every instance counts on its own and restarts
+/// reset them.
+#[derive(Default)]
+pub struct MemoryStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn take(&self, key: &str, limit: &Limit) -> Result<Option<Duration>, AppError> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let bucket = buckets
+            .entry(key.to_string())
+            .or_insert(Bucket { tokens: limit.burst as f64, updated: now });
+
+        let (tokens, wait) = limit.take(bucket.tokens, now.duration_since(bucket.updated));
+        bucket.tokens = tokens;
+        bucket.updated = now;
+        Ok(wait)
+    }
+
+    async fn purge(&self, idle: Duration) -> Result<u64, AppError> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let before = buckets.len();
+        buckets.retain(|_, bucket| bucket.updated.elapsed() < idle);
+        Ok((before - buckets.len()) as u64)
+    }
+
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+}
@@ -0,0 +1,302 @@
+//! Token-bucket rate limiting of the `/api` routes.
+//!
+//! Every request is sorted into a `RouteGroup`, each with its own limit from
+//! `AppConfig.rate_limit`. A bucket holds up to `burst` tokens and refills at `per_minute`; a
+//! request takes one token or is answered with `429` and `Retry-After`.
+
+pub mod memory;
+pub mod postgres;
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, Error, HttpMessage};
+use async_trait::async_trait;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::config::RateLimitConfig;
+use crate::errors::AppError;
+use crate::extractors::auth::VerifiedApiKey;
+use crate::services::api_key_service::ApiKeyService;
+
+/// Routes sharing a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteGroup {
+    /// `POST /api/auth/login`.
+    Auth,
+    /// Endpoints receiving image files, which are forwarded to the storage backend.
+    Upload,
+    /// Every other `POST`, `PUT`, `PATCH` and `DELETE`.
+    Write,
+    /// `GET` requests.
+    Read,
+}
+
+impl RouteGroup {
+    pub const ALL: [RouteGroup; 4] = [RouteGroup::Auth, RouteGroup::Upload, RouteGroup::Write, RouteGroup::Read];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouteGroup::Auth => "auth",
+            RouteGroup::Upload => "upload",
+            RouteGroup::Write => "write",
+            RouteGroup::Read => "read",
+        }
+    }
+
+    /// `pattern` is the route the request matched, e.g. `/api/projects/{id}/images/upload`.
+    /// Health probes, static files and CORS preflights belong to no group.
+    pub fn of(method: &Method, pattern: &str) -> Option<Self> {
+        if !pattern.starts_with("/api/") || method == Method::OPTIONS {
+            return None;
+        }
+        Some(if pattern == "/api/auth/login" {
+            RouteGroup::Auth
+        } else if pattern.ends_with("/upload") || pattern.ends_with("-with-upload") {
+            RouteGroup::Upload
+        } else if method == Method::GET || method == Method::HEAD {
+            RouteGroup::Read
+        } else {
+            RouteGroup::Write
+        })
+    }
+}
+
+impl fmt::Display for RouteGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a bucket belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyBy {
+    /// The client IP address.
+    Ip,
+    /// The key sent in `X-Api-Key`, so machine clients behind one address don't share a
+    /// bucket. Requests without a valid key fall back to their IP address: counting unknown
+    /// keys separately would hand out a fresh bucket per made-up key.
+    ApiKey,
+}
+
+impl FromStr for KeyBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "ip" => Ok(KeyBy::Ip),
+            "api_key" => Ok(KeyBy::ApiKey),
+            other => Err(format!("unknown key '{}', expected ip or api_key", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Limit {
+    pub per_minute: u32,
+    /// Bucket size: how many requests may be made at once after a quiet period.
+    pub burst: u32,
+    pub key: KeyBy,
+}
+
+impl Limit {
+    pub fn tokens_per_sec(&self) -> f64 {
+        self.per_minute as f64 / 60.0
+    }
+
+    /// Time for an empty bucket to fill up; an idle bucket older than that can be forgotten.
+    pub fn refill_time(&self) -> Duration {
+        Duration::from_secs_f64(self.burst as f64 / self.tokens_per_sec())
+    }
+
+    /// How long until a bucket holding `tokens` has a whole token again.
+    fn retry_after(&self, tokens: f64) -> Duration {
+        Duration::from_secs_f64((1.0 - tokens).max(0.0) / self.tokens_per_sec())
+    }
+
+    /// Refills a bucket that held `tokens` `elapsed` ago, then takes a token from it. Returns
+    /// the tokens left and, when there was no whole token to take, how long to wait.
+    fn take(&self, tokens: f64, elapsed: Duration) -> (f64, Option<Duration>) {
+        let tokens = (tokens + elapsed.as_secs_f64() * self.tokens_per_sec()).min(self.burst as f64);
+        if tokens >= 1.0 {
+            (tokens - 1.0, None)
+        } else {
+            (tokens, Some(self.retry_after(tokens)))
+        }
+    }
+}
+
+/// Where buckets are kept.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Takes a token from the bucket `key`, creating a full one if needed. Returns how long
+    /// to wait when the bucket is empty.
+    async fn take(&self, key: &str, limit: &Limit) -> Result<Option<Duration>, AppError>;
+
+    /// Forgets buckets untouched for `idle`, returning how many were removed.
+    async fn purge(&self, idle: Duration) -> Result<u64, AppError>;
+
+    fn name(&self) -> &'static str;
+}
+
+pub struct RateLimiter {
+    pub config: RateLimitConfig,
+    pub store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimiter {
+    /// Removes buckets that have been idle long enough to be full again.
+    pub async fn purge(&self) -> Result<u64, AppError> {
+        let idle = RouteGroup::ALL
+            .iter()
+            .filter_map(|group| self.config.limit(*group))
+            .map(Limit::refill_time)
+            .max()
+            .unwrap_or_default();
+        self.store.purge(idle.max(Duration::from_secs(60))).await
+    }
+
+    /// `None` when the request may go through.
+    async fn check(&self, req: &ServiceRequest) -> Option<Duration> {
+        let group = RouteGroup::of(req.method(), &req.match_pattern()?)?;
+        let limit = self.config.limit(group)?;
+        let key = format!("{}:{}", group, self.client_key(req, limit.key).await?);
+        match self.store.take(&key, limit).await {
+            Ok(wait) => wait,
+            // An unreachable store must not take the API down with it
+            Err(e) => {
+                eprintln!("⚠️  Rate limit store failed, request let through: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn client_key(&self, req: &ServiceRequest, key: KeyBy) -> Option<String> {
+        if key == KeyBy::ApiKey {
+            if let Some(id) = verify_api_key(req).await {
+                return Some(format!("key:{}", id));
+            }
+        }
+        self.client_ip(req).map(|ip| format!("ip:{}", ip))
+    }
+
+    /// Behind a proxy the last `X-Forwarded-For` entry is the address the proxy saw; the
+    /// earlier ones are set by the client and can't be trusted.
+    fn client_ip(&self, req: &ServiceRequest) -> Option<String> {
+        if self.config.trust_proxy {
+            let forwarded = req
+                .headers()
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').next())
+                .map(str::trim)
+                .filter(|ip| !ip.is_empty());
+            if let Some(ip) = forwarded {
+                return Some(ip.to_string());
+            }
+        }
+        req.peer_addr().map(|addr| addr.ip().to_string())
+    }
+}
+
+/// Id of the key in `X-Api-Key` once `ApiKeyService` accepted it. The key is kept in the
+/// request extensions, so `AuthUser` doesn't look it up a second time.
+async fn verify_api_key(req: &ServiceRequest) -> Option<uuid::Uuid> {
+    if let Some(verified) = req.extensions().get::<VerifiedApiKey>() {
+        return Some(verified.api_key.id);
+    }
+    let key = req
+        .headers()
+        .get("x-api-key")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty())?;
+    let api_keys = req.app_data::<web::Data<ApiKeyService>>()?;
+    let (api_key, role) = api_keys.authenticate(key).await.ok()?;
+    let id = api_key.id;
+    req.extensions_mut().insert(VerifiedApiKey { api_key, role });
+    Some(id)
+}
+
+/// Answers `429` once the bucket of the request's group and client is empty. Does nothing
+/// unless a `RateLimiter` is registered as app data.
+pub async fn middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let limiter = req.app_data::<web::Data<RateLimiter>>().cloned();
+    if let Some(limiter) = limiter {
+        if let Some(wait) = limiter.check(&req).await {
+            // Rounded up: retrying after a truncated delay would be refused again
+            let error = AppError::RateLimited { retry_after_secs: wait.as_secs_f64().ceil().max(1.0) as u64 };
+            return Ok(req.error_response(error).map_into_right_body());
+        }
+    }
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(per_minute: u32, burst: u32) -> Limit {
+        Limit { per_minute, burst, key: KeyBy::Ip }
+    }
+
+    #[test]
+    fn a_full_bucket_allows_a_burst_then_waits_for_a_token() {
+        let limit = limit(60, 3);
+        let mut tokens = limit.burst as f64;
+        for _ in 0..3 {
+            let (left, wait) = limit.take(tokens, Duration::ZERO);
+            assert_eq!(wait, None);
+            tokens = left;
+        }
+        let (left, wait) = limit.take(tokens, Duration::ZERO);
+        assert_eq!(left, 0.0);
+        assert_eq!(wait, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn buckets_refill_at_the_rate_up_to_the_burst() {
+        let limit = limit(30, 5);
+        // Half a token after a second at 30 per minute: not enough yet
+        let (left, wait) = limit.take(0.0, Duration::from_secs(1));
+        assert_eq!(left, 0.5);
+        assert_eq!(wait, Some(Duration::from_secs(1)));
+
+        let (left, wait) = limit.take(0.0, Duration::from_secs(4));
+        assert_eq!((left, wait), (1.0, None));
+
+        // Idle for an hour: capped at the burst, not 30 tokens
+        let (left, wait) = limit.take(2.0, Duration::from_secs(3600));
+        assert_eq!((left, wait), (4.0, None));
+        assert_eq!(limit.refill_time(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn routes_are_grouped_by_pattern_and_method() {
+        let cases = [
+            (Method::POST, "/api/auth/login", Some(RouteGroup::Auth)),
+            (Method::POST, "/api/upload", Some(RouteGroup::Upload)),
+            (Method::POST, "/api/projects/{id}/images/upload", Some(RouteGroup::Upload)),
+            (Method::POST, "/api/projects/create-with-upload", Some(RouteGroup::Upload)),
+            (Method::POST, "/api/projects", Some(RouteGroup::Write)),
+            (Method::PUT, "/api/projects/{id}", Some(RouteGroup::Write)),
+            (Method::PATCH, "/api/projects/{id}/restore", Some(RouteGroup::Write)),
+            (Method::DELETE, "/api/auth/api-keys/{id}", Some(RouteGroup::Write)),
+            (Method::GET, "/api/projects", Some(RouteGroup::Read)),
+            (Method::HEAD, "/api/employees/{id}", Some(RouteGroup::Read)),
+            (Method::OPTIONS, "/api/projects", None),
+            (Method::GET, "/healthz", None),
+            (Method::GET, "/uploads/{filename}", None),
+            (Method::GET, "/api", None),
+        ];
+        for (method, pattern, group) in cases {
+            assert_eq!(RouteGroup::of(&method, pattern), group, "{} {}", method, pattern);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use std::time::Duration;
+use crate::errors::AppError;
+use super::{Limit, RateLimitStore};
+
+/// Tokens in bucket `b` after refilling at `$3` per second since its last update, capped at `$2`.
+const REFILLED: &str = "LEAST($2::float8, b.tokens + EXTRACT(EPOCH FROM now() - b.updated_at)::float8 * $3::float8)";
+
+/// Buckets in the `rate_limit_buckets` table, so that every instance behind a load balancer
+/// shares them. Each request costs one round trip.
+pub struct PostgresStore {
+    pub pool: Pool,
+}
+
+#[async_trait]
+impl RateLimitStore for PostgresStore {
+    async fn take(&self, key: &str, limit: &Limit) -> Result<Option<Duration>, AppError> {
+        let client = self.pool.get().await?;
+        // One statement, so concurrent requests on any instance serialise on the row lock
+        let query = format!(
+            "INSERT INTO rate_limit_buckets AS b (key, tokens, allowed, updated_at)
+             VALUES ($1, $2::float8 - 1, TRUE, now())
+             ON CONFLICT (key) DO UPDATE SET
+                tokens = CASE WHEN {refilled} >= 1 THEN {refilled} - 1 ELSE {refilled} END,
+                allowed = {refilled} >= 1,
+                updated_at = now()
+             RETURNING tokens, allowed",
+            refilled = REFILLED
+        );
+        let row = client
+            .query_one(&query, &[&key, &(limit.burst as f64), &limit.tokens_per_sec()])
+            .await?;
+        let allowed: bool = row.get("allowed");
+        Ok((!allowed).then(|| limit.retry_after(row.get("tokens"))))
+    }
+
+    async fn purge(&self, idle: Duration) -> Result<u64, AppError> {
+        let client = self.pool.get().await?;
+        let deleted = client
+            .execute(
+                "DELETE FROM rate_limit_buckets WHERE updated_at < now() - make_interval(secs => $1)",
+                &[&idle.as_secs_f64()],
+            )
+            .await?;
+        Ok(deleted)
+    }
+
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+}
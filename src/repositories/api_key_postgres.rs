@@ -0,0 +1,115 @@
+use crate::models::api_key::ApiKey;
+use crate::models::user::Role;
+use crate::errors::AppError;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Pool;
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+const COLUMNS: &str = "id, name, prefix, scopes, user_id, created_at, expires_at, last_used_at, revoked_at";
+
+pub struct ApiKeyPostgresRepo {
+    pub pool: Pool,
+}
+
+fn from_row(row: &Row) -> Result<ApiKey, AppError> {
+    let scopes: Vec<String> = row.get("scopes");
+    Ok(ApiKey {
+        id: row.get("id"),
+        name: row.get("name"),
+        prefix: row.get("prefix"),
+        scopes: scopes
+            .iter()
+            .map(|scope| scope.parse())
+            .collect::<Result<_, _>>()
+            .map_err(AppError::Internal)?,
+        user_id: row.get("user_id"),
+        created_at: row.get("created_at"),
+        expires_at: row.get("expires_at"),
+        last_used_at: row.get("last_used_at"),
+        revoked_at: row.get("revoked_at"),
+    })
+}
+
+impl ApiKeyPostgresRepo {
+    pub async fn add(
+        &self,
+        name: &str,
+        prefix: &str,
+        key_hash: &str,
+        scopes: &[String],
+        user_id: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ApiKey, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(&format!(
+                "INSERT INTO api_keys (name, prefix, key_hash, scopes, user_id, expires_at)
+                 VALUES ($1, $2, $3, $4, $5, $6) RETURNING {}",
+                COLUMNS
+            ))
+            .await?;
+        let row = client
+            .query_one(&stmt, &[&name, &prefix, &key_hash, &scopes, &user_id, &expires_at])
+            .await?;
+        from_row(&row)
+    }
+
+    /// Newest first, revoked keys included.
+    pub async fn list(&self) -> Result<Vec<ApiKey>, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(&format!("SELECT {} FROM api_keys ORDER BY created_at DESC", COLUMNS))
+            .await?;
+        let rows = client.query(&stmt, &[]).await?;
+        rows.iter().map(from_row).collect()
+    }
+
+    /// The key with this hash if it is neither revoked nor expired, with the role of its user.
+    pub async fn find_active(&self, key_hash: &str) -> Result<Option<(ApiKey, Role)>, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(
+                "SELECT k.id, k.name, k.prefix, k.scopes, k.user_id, k.created_at, k.expires_at,
+                        k.last_used_at, k.revoked_at, u.role
+                 FROM api_keys k JOIN users u ON u.id = k.user_id
+                 WHERE k.key_hash = $1 AND k.revoked_at IS NULL
+                   AND (k.expires_at IS NULL OR k.expires_at > now())"
+            )
+            .await?;
+        let Some(row) = client.query_opt(&stmt, &[&key_hash]).await? else {
+            return Ok(None);
+        };
+        let role: String = row.get("role");
+        Ok(Some((from_row(&row)?, role.parse().map_err(AppError::Internal)?)))
+    }
+
+    /// Skipped when the last recorded use is less than a minute old, so a busy client
+    /// doesn't write on every request.
+    pub async fn touch(&self, id: Uuid) -> Result<(), AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(
+                "UPDATE api_keys SET last_used_at = now()
+                 WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < now() - INTERVAL '1 minute')"
+            )
+            .await?;
+        client.execute(&stmt, &[&id]).await?;
+        Ok(())
+    }
+
+    pub async fn revoke(&self, id: Uuid) -> Result<ApiKey, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(&format!(
+                "UPDATE api_keys SET revoked_at = now() WHERE id = $1 AND revoked_at IS NULL RETURNING {}",
+                COLUMNS
+            ))
+            .await?;
+        let row = client
+            .query_opt(&stmt, &[&id])
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Active API key {} not found", id)))?;
+        from_row(&row)
+    }
+}
@@ -0,0 +1,94 @@
+use crate::models::audit::{AuditAction, AuditEntity, AuditEntry};
+use crate::dtos::audit_dto::AuditQuery;
+use crate::dtos::pagination_dto::PageRequest;
+use crate::errors::AppError;
+use crate::models::pagination::Page;
+use deadpool_postgres::Pool;
+use serde_json::Value;
+use tokio_postgres::Row;
+use tokio_postgres::types::Json;
+use uuid::Uuid;
+
+/// Columns read by `from_row`; `a` is the `audit_log` alias, `u` the joined actor.
+const COLUMNS: &str = "a.id, a.actor_id, u.email AS actor_email, a.action, a.entity, a.entity_id, a.changes, a.created_at";
+
+/// Filters shared by the count and the page query, using parameters `$1..$6`.
+const FILTER: &str = "($1::text IS NULL OR a.entity = $1) 
+    AND ($2::uuid IS NULL OR a.entity_id = $2) 
+    AND ($3::uuid IS NULL OR a.actor_id = $3) 
+    AND ($4::text IS NULL OR a.action = $4) 
+    AND ($5::timestamptz IS NULL OR a.created_at >= $5) 
+    AND ($6::timestamptz IS NULL OR a.created_at < $6)";
+
+pub struct AuditPostgresRepo {
+    pub pool: Pool,
+}
+
+fn from_row(row: Row) -> Result<AuditEntry, AppError> {
+    let action: String = row.get("action");
+    let entity: String = row.get("entity");
+    let Json(changes) = row.get("changes");
+    Ok(AuditEntry {
+        id: row.get("id"),
+        actor_id: row.get("actor_id"),
+        actor_email: row.get("actor_email"),
+        action: action.parse().map_err(AppError::Internal)?,
+        entity: entity.parse().map_err(AppError::Internal)?,
+        entity_id: row.get("entity_id"),
+        changes,
+        created_at: row.get("created_at"),
+    })
+}
+
+impl AuditPostgresRepo {
+    pub async fn add(
+        &self,
+        actor_id: Option<Uuid>,
+        action: AuditAction,
+        entity: AuditEntity,
+        entity_id: Uuid,
+        changes: &Value,
+    ) -> Result<(), AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(
+                "INSERT INTO audit_log (actor_id, action, entity, entity_id, changes) 
+                 VALUES ($1, $2, $3, $4, $5)"
+            )
+            .await?;
+        client
+            .execute(&stmt, &[&actor_id, &action.as_str(), &entity.as_str(), &entity_id, &Json(changes)])
+            .await?;
+        Ok(())
+    }
+
+    /// Newest entries first.
+    pub async fn list(&self, query: &AuditQuery, page: PageRequest) -> Result<Page<AuditEntry>, AppError> {
+        let client = self.pool.get().await?;
+        let entity = query.entity.map(|e| e.as_str());
+        let action = query.action.map(|a| a.as_str());
+
+        let count_stmt = client
+            .prepare(&format!("SELECT COUNT(*) FROM audit_log a WHERE {FILTER}"))
+            .await?;
+        let total: i64 = client
+            .query_one(&count_stmt, &[&entity, &query.id, &query.actor, &action, &query.from, &query.to])
+            .await?
+            .get(0);
+
+        let stmt = client
+            .prepare(&format!(
+                "SELECT {COLUMNS} FROM audit_log a 
+                 LEFT JOIN users u ON u.id = a.actor_id 
+                 WHERE {FILTER} 
+                 ORDER BY a.created_at DESC, a.id DESC 
+                 LIMIT $7 OFFSET $8"
+            ))
+            .await?;
+        let rows = client
+            .query(&stmt, &[&entity, &query.id, &query.actor, &action, &query.from, &query.to, &page.limit, &page.offset])
+            .await?;
+        let items = rows.into_iter().map(from_row).collect::<Result<Vec<_>, _>>()?;
+        Ok(Page { items, total })
+    }
+}
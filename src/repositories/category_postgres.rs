@@ -0,0 +1,129 @@
+use crate::models::category::Category;
+use crate::dtos::category_dto::{CreateCategoryDTO, UpdateCategoryDTO};
+use crate::errors::AppError;
+use deadpool_postgres::Pool;
+use tokio_postgres::Row;
+use tokio_postgres::error::SqlState;
+use uuid::Uuid;
+
+/// Columns read by `from_row`; `c` is the `categories` alias.
+const COLUMNS: &str = "c.id, c.slug, c.name, c.description, c.icon, c.sort_order, c.created_at, c.updated_at, 
+    (SELECT COUNT(*) FROM projects p WHERE p.category = c.slug AND p.deleted_at IS NULL) AS project_count";
+
+pub struct CategoryPostgresRepo {
+    pub pool: Pool,
+}
+
+fn from_row(row: Row) -> Category {
+    Category {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        name: row.get("name"),
+        description: row.get("description"),
+        icon: row.get("icon"),
+        sort_order: row.get("sort_order"),
+        project_count: row.get("project_count"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn slug_conflict(err: tokio_postgres::Error) -> AppError {
+    match AppError::from(err) {
+        AppError::Conflict(_) => AppError::Conflict("A category with this slug already exists".to_string()),
+        other => other,
+    }
+}
+
+fn not_found(id: Uuid) -> AppError {
+    AppError::NotFound(format!("Category {} not found", id))
+}
+
+impl CategoryPostgresRepo {
+    pub async fn add(&self, dto: CreateCategoryDTO) -> Result<Category, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(&format!(
+                "WITH c AS (
+                    INSERT INTO categories (id, slug, name, description, icon, sort_order) 
+                    VALUES ($1, $2, $3, $4, $5, $6) 
+                    RETURNING *
+                 ) 
+                 SELECT {COLUMNS} FROM c"
+            ))
+            .await?;
+        let row = client
+            .query_one(&stmt, &[&Uuid::new_v4(), &dto.slug, &dto.name, &dto.description, &dto.icon, &dto.sort_order])
+            .await
+            .map_err(slug_conflict)?;
+        Ok(from_row(row))
+    }
+
+    /// Ordered for display: `sort_order`, then name.
+    pub async fn list(&self) -> Result<Vec<Category>, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(&format!("SELECT {COLUMNS} FROM categories c ORDER BY c.sort_order, c.name"))
+            .await?;
+        let rows = client.query(&stmt, &[]).await?;
+        Ok(rows.into_iter().map(from_row).collect())
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Category, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(&format!("SELECT {COLUMNS} FROM categories c WHERE c.id = $1"))
+            .await?;
+        let row = client
+            .query_opt(&stmt, &[&id])
+            .await?
+            .ok_or_else(|| not_found(id))?;
+        Ok(from_row(row))
+    }
+
+    pub async fn update(&self, id: Uuid, dto: UpdateCategoryDTO) -> Result<Category, AppError> {
+        let client = self.pool.get().await?;
+        let current = self.get_by_id(id).await?;
+
+        let slug = dto.slug.unwrap_or(current.slug);
+        let name = dto.name.unwrap_or(current.name);
+        let description = dto.description.unwrap_or(current.description);
+        let icon = dto.icon.unwrap_or(current.icon);
+        let sort_order = dto.sort_order.unwrap_or(current.sort_order);
+
+        // Projects follow a slug change through ON UPDATE CASCADE
+        let stmt = client
+            .prepare(
+                "UPDATE categories 
+                 SET slug = $1, name = $2, description = $3, icon = $4, sort_order = $5, updated_at = CURRENT_TIMESTAMP 
+                 WHERE id = $6"
+            )
+            .await?;
+        let res = client
+            .execute(&stmt, &[&slug, &name, &description, &icon, &sort_order, &id])
+            .await
+            .map_err(slug_conflict)?;
+        if res == 0 {
+            return Err(not_found(id));
+        }
+        // Re-read so `project_count` sees the cascaded slug
+        self.get_by_id(id).await
+    }
+
+    /// Categories still used by projects cannot be deleted.
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client.prepare("DELETE FROM categories WHERE id = $1").await?;
+        let res = client.execute(&stmt, &[&id]).await.map_err(|err| {
+            if err.code() == Some(&SqlState::FOREIGN_KEY_VIOLATION) {
+                AppError::Conflict("Category is still used by projects (including trashed ones); move them to another category first".to_string())
+            } else {
+                AppError::from(err)
+            }
+        })?;
+        if res == 0 {
+            return Err(not_found(id));
+        }
+        Ok(())
+    }
+}
@@ -1,10 +1,19 @@
 use crate::models::employee::Employee;
-use crate::dtos::employee_dto::{CreateEmployeeDTO, UpdateEmployeeDTO};
+use crate::dtos::employee_dto::{CreateEmployeeDTO, EmployeeListQuery, EmployeeSortField, UpdateEmployeeDTO};
+use crate::dtos::pagination_dto::{PageRequest, SortOrder};
+use crate::errors::AppError;
+use crate::models::pagination::Page;
+use crate::models::search::{headline_sql, SearchHit};
+use chrono::{DateTime, Utc};
 use deadpool_postgres::Pool;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 use tokio_postgres::Row;
 use tokio_postgres::types::ToSql;
 
+/// Columns read by `from_row`.
+const COLUMNS: &str = "id, name, position, email, created_at, updated_at, deleted_at";
+
 pub struct EmployeePostgresRepo {
     pub pool: Pool,
 }
@@ -15,48 +24,159 @@ fn from_row(row: Row) -> Employee {
         name: row.get("name"),
         position: row.get("position"),
         email: row.get("email"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        created_at_display: None,
+        updated_at_display: None,
+        deleted_at: row.get("deleted_at"),
+    }
+}
+
+fn email_conflict(err: tokio_postgres::Error) -> AppError {
+    match AppError::from(err) {
+        AppError::Conflict(_) => AppError::Conflict("An employee with this email already exists".to_string()),
+        other => other,
+    }
+}
+
+fn sort_column(field: EmployeeSortField) -> &'static str {
+    match field {
+        EmployeeSortField::Name => "name",
+        EmployeeSortField::Position => "position",
+        EmployeeSortField::Email => "email",
+        EmployeeSortField::CreatedAt => "created_at",
+        EmployeeSortField::UpdatedAt => "updated_at",
     }
 }
+
+fn not_found(id: Uuid) -> AppError {
+    AppError::NotFound(format!("Employee {} not found", id))
+}
  
 impl EmployeePostgresRepo {
-    pub async fn add(&self, dto: CreateEmployeeDTO) -> Result<Employee, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+    pub async fn add(&self, dto: CreateEmployeeDTO) -> Result<Employee, AppError> {
+        let client = self.pool.get().await?;
         let id = Uuid::new_v4();
-        let stmt = client.prepare("INSERT INTO employees (id, name, position, email) VALUES ($1, $2, $3, $4) RETURNING id, name, position, email").await.map_err(|e| e.to_string())?;
-        let row = client.query_one(&stmt, &[&id as &(dyn ToSql + Sync), &dto.name, &dto.position, &dto.email]).await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!("INSERT INTO employees (id, name, position, email) VALUES ($1, $2, $3, $4) RETURNING {COLUMNS}")).await?;
+        let row = client.query_one(&stmt, &[&id as &(dyn ToSql + Sync), &dto.name, &dto.position, &dto.email]).await.map_err(email_conflict)?;
         Ok(from_row(row))
     }
 
-    pub async fn get_all(&self) -> Result<Vec<Employee>, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
-        let stmt = client.prepare("SELECT id, name, position, email FROM employees").await.map_err(|e| e.to_string())?;
-        let rows = client.query(&stmt, &[]).await.map_err(|e| e.to_string())?;
-        Ok(rows.into_iter().map(from_row).collect())
+    pub async fn list(&self, query: &EmployeeListQuery, page: PageRequest) -> Result<Page<Employee>, AppError> {
+        let client = self.pool.get().await?;
+        let column = sort_column(query.sort.unwrap_or(EmployeeSortField::Name));
+        let order = query.order.unwrap_or(SortOrder::Asc).as_sql();
+
+        let total: i64 = client.query_one("SELECT COUNT(*) FROM employees WHERE deleted_at IS NULL", &[]).await?.get(0);
+        let stmt = client.prepare(&format!("SELECT {COLUMNS} FROM employees WHERE deleted_at IS NULL ORDER BY {column} {order}, id {order} LIMIT $1 OFFSET $2")).await?;
+        let rows = client.query(&stmt, &[&page.limit, &page.offset]).await?;
+        Ok(Page {
+            items: rows.into_iter().map(from_row).collect(),
+            total,
+        })
     }
 
-    pub async fn get_by_id(&self, id: Uuid) -> Result<Employee, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
-        let stmt = client.prepare("SELECT id, name, position, email FROM employees WHERE id = $1").await.map_err(|e| e.to_string())?;
-        let row = client.query_one(&stmt, &[&id as &(dyn ToSql + Sync)]).await.map_err(|e| e.to_string())?;
+    /// Ranked full-text search. Results are ordered by relevance unless `sort` is given.
+    pub async fn search(&self, term: &str, query: &EmployeeListQuery, page: PageRequest) -> Result<Page<SearchHit<Employee>>, AppError> {
+        let client = self.pool.get().await?;
+        let order_by = match query.sort {
+            Some(field) => {
+                let order = query.order.unwrap_or(SortOrder::Asc).as_sql();
+                format!("{} {order}, id {order}", sort_column(field))
+            }
+            None => "rank DESC, name ASC, id ASC".to_string(),
+        };
+
+        let total: i64 = client
+            .query_one("SELECT COUNT(*) FROM employees WHERE search_vector @@ websearch_to_tsquery('simple', $1) AND deleted_at IS NULL", &[&term])
+            .await?
+            .get(0);
+        let name_headline = headline_sql("name");
+        let position_headline = headline_sql("position");
+        let stmt = client.prepare(&format!(
+            "SELECT {COLUMNS}, 
+                    ts_rank_cd(search_vector, tsq) AS rank, 
+                    {name_headline} AS name_highlight, 
+                    {position_headline} AS position_highlight 
+             FROM employees, websearch_to_tsquery('simple', $1) AS tsq 
+             WHERE search_vector @@ tsq AND deleted_at IS NULL 
+             ORDER BY {order_by} 
+             LIMIT $2 OFFSET $3"
+        )).await?;
+        let rows = client.query(&stmt, &[&term, &page.limit, &page.offset]).await?;
+        let items = rows
+            .into_iter()
+            .map(|row| {
+                let rank: f32 = row.get("rank");
+                let highlights = BTreeMap::from([
+                    ("name", row.get("name_highlight")),
+                    ("position", row.get("position_highlight")),
+                ]);
+                SearchHit { item: from_row(row), rank, highlights }
+            })
+            .collect();
+        Ok(Page { items, total })
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Employee, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client.prepare(&format!("SELECT {COLUMNS} FROM employees WHERE id = $1 AND deleted_at IS NULL")).await?;
+        let row = client.query_opt(&stmt, &[&id as &(dyn ToSql + Sync)]).await?.ok_or_else(|| not_found(id))?;
         Ok(from_row(row))
     }
 
-    pub async fn update(&self, id: Uuid, dto: UpdateEmployeeDTO) -> Result<Employee, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+    pub async fn update(&self, id: Uuid, dto: UpdateEmployeeDTO) -> Result<Employee, AppError> {
+        let client = self.pool.get().await?;
         let current = self.get_by_id(id).await?;
         let name = dto.name.unwrap_or(current.name);
         let position = dto.position.unwrap_or(current.position);
         let email = dto.email.unwrap_or(current.email);
 
-        let stmt = client.prepare("UPDATE employees SET name = $1, position = $2, email = $3 WHERE id = $4 RETURNING id, name, position, email").await.map_err(|e| e.to_string())?;
-        let row = client.query_one(&stmt, &[&name, &position, &email, &id as &(dyn ToSql + Sync)]).await.map_err(|e| e.to_string())?;
+        let stmt = client.prepare(&format!("UPDATE employees SET name = $1, position = $2, email = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $4 AND deleted_at IS NULL RETURNING {COLUMNS}")).await?;
+        let row = client.query_opt(&stmt, &[&name, &position, &email, &id as &(dyn ToSql + Sync)]).await.map_err(email_conflict)?.ok_or_else(|| not_found(id))?;
         Ok(from_row(row))
     }
 
-    pub async fn delete(&self, id: Uuid) -> Result<u64, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
-        let stmt = client.prepare("DELETE FROM employees WHERE id = $1").await.map_err(|e| e.to_string())?;
-        let res = client.execute(&stmt, &[&id as &(dyn ToSql + Sync)]).await.map_err(|e| e.to_string())?;
-        Ok(res)
+    /// Moves the employee to the trash; it is removed for good by `purge`.
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client.prepare("UPDATE employees SET deleted_at = CURRENT_TIMESTAMP WHERE id = $1 AND deleted_at IS NULL").await?;
+        let res = client.execute(&stmt, &[&id as &(dyn ToSql + Sync)]).await?;
+        if res == 0 {
+            return Err(not_found(id));
+        }
+        Ok(())
+    }
+
+    /// Trashed employees, most recently deleted first.
+    pub async fn trash(&self) -> Result<Vec<Employee>, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client.prepare(&format!("SELECT {COLUMNS} FROM employees WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id")).await?;
+        let rows = client.query(&stmt, &[]).await?;
+        Ok(rows.into_iter().map(from_row).collect())
+    }
+
+    pub async fn restore(&self, id: Uuid) -> Result<Employee, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client.prepare(&format!("UPDATE employees SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP WHERE id = $1 AND deleted_at IS NOT NULL RETURNING {COLUMNS}")).await?;
+        let row = client
+            .query_opt(&stmt, &[&id as &(dyn ToSql + Sync)])
+            .await
+            .map_err(|e| match AppError::from(e) {
+                AppError::Conflict(_) => {
+                    AppError::Conflict("Another employee already uses this email; change it before restoring".to_string())
+                }
+                other => other,
+            })?
+            .ok_or_else(|| AppError::NotFound(format!("Employee {} is not in the trash", id)))?;
+        Ok(from_row(row))
+    }
+
+    /// Permanently deletes employees trashed before `cutoff` and returns them.
+    pub async fn purge(&self, cutoff: DateTime<Utc>) -> Result<Vec<Employee>, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client.prepare(&format!("DELETE FROM employees WHERE deleted_at < $1 RETURNING {COLUMNS}")).await?;
+        let rows = client.query(&stmt, &[&cutoff]).await?;
+        Ok(rows.into_iter().map(from_row).collect())
     }
 }
@@ -1,2 +1,6 @@
+pub mod api_key_postgres;
+pub mod audit_postgres;
+pub mod category_postgres;
 pub mod employee_postgres;
 pub mod project_postgres;
+pub mod user_postgres;
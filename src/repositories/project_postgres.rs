@@ -1,134 +1,952 @@
-use crate::models::project::Project;
-use crate::dtos::project_dto::{CreateProjectDTO, UpdateProjectDTO};
+use crate::models::project::{Project, ProjectImage, ProjectStatus, SlugLookup};
+use crate::models::tag::{Tag, TagCount};
+use crate::models::revision::{ProjectChange, ProjectContent, ProjectRevision};
+use crate::dtos::project_dto::{CreateProjectDTO, NewProjectImageDTO, ProjectListQuery, ProjectSortField, ReorderImagesDTO, TagMatch, UpdateProjectDTO, UpdateProjectImageDTO};
+use crate::slug::slugify;
+use crate::dtos::pagination_dto::{PageRequest, SortOrder};
+use crate::errors::AppError;
+use crate::models::pagination::Page;
+use crate::models::search::{headline_sql, SearchHit};
+use chrono::{DateTime, Utc};
 use deadpool_postgres::Pool;
 use uuid::Uuid;
-use tokio_postgres::Row;
-use tokio_postgres::types::{Type, ToSql};
-use std::time::SystemTime;
+use tokio_postgres::{Client, GenericClient, Row};
+use tokio_postgres::error::SqlState;
+use tokio_postgres::types::{Json, ToSql};
+use crate::validation::FieldErrors;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Columns read by `from_row`.
+const COLUMNS: &str = "id, name, slug, description, category, status, publish_at, created_at, updated_at, deleted_at";
+/// Columns read by `revision_from_row`; `r` is the revision, `u` the joined actor.
+const REVISION_COLUMNS: &str = "r.id, r.project_id, r.revision, r.content, r.actor_id, u.email AS actor_email, r.created_at";
+/// Columns read by `image_from_row`.
+const IMAGE_COLUMNS: &str = "id, project_id, url, alt, caption, position, is_cover, width, height, blurhash, renditions";
 
 pub struct ProjectPostgresRepo {
     pub pool: Pool,
 }
 
+/// Images and tags are loaded separately, see `attach_details`.
 fn from_row(row: Row) -> Project {
-    // Get images array directly from PostgreSQL
-    let images: Vec<String> = row.get("images");
-    
-    // Get timestamps as SystemTime and convert to string
-    let created_at: Option<SystemTime> = row.get("created_at");
-    let updated_at: Option<SystemTime> = row.get("updated_at");
-    
     Project {
         id: row.get::<_, Uuid>("id"),
         name: row.get("name"),
+        slug: row.get("slug"),
         description: row.get("description"),
-        images,
+        images: Vec::new(),
         category: row.get("category"),
-        created_at: created_at.map(|_| chrono::Utc::now().format("%d/%m/%Y %H:%M").to_string()),
-        updated_at: updated_at.map(|_| chrono::Utc::now().format("%d/%m/%Y %H:%M").to_string()),
+        tags: Vec::new(),
+        // Guarded by a CHECK constraint; anything unexpected stays hidden as a draft
+        status: row.get::<_, String>("status").parse().unwrap_or(ProjectStatus::Draft),
+        publish_at: row.get("publish_at"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        created_at_display: None,
+        updated_at_display: None,
+        deleted_at: row.get("deleted_at"),
+    }
+}
+
+fn image_from_row(row: &Row) -> ProjectImage {
+    let Json(renditions) = row.get("renditions");
+    ProjectImage {
+        id: row.get("id"),
+        url: row.get("url"),
+        alt: row.get("alt"),
+        caption: row.get("caption"),
+        position: row.get("position"),
+        is_cover: row.get("is_cover"),
+        width: row.get::<_, Option<i32>>("width").map(|w| w as u32),
+        height: row.get::<_, Option<i32>>("height").map(|h| h as u32),
+        blurhash: row.get("blurhash"),
+        renditions,
+    }
+}
+
+fn revision_from_row(row: &Row) -> ProjectRevision {
+    let Json(content) = row.get("content");
+    ProjectRevision {
+        id: row.get("id"),
+        project_id: row.get("project_id"),
+        revision: row.get("revision"),
+        content,
+        actor_id: row.get("actor_id"),
+        actor_email: row.get("actor_email"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Loads the images and tags of all given projects with one query each.
+async fn attach_details<'a, I>(client: &Client, projects: I) -> Result<(), AppError>
+where
+    I: IntoIterator<Item = &'a mut Project>,
+{
+    let mut projects: Vec<&mut Project> = projects.into_iter().collect();
+    if projects.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<Uuid> = projects.iter().map(|p| p.id).collect();
+    attach_images(client, &ids, &mut projects).await?;
+    attach_tags(client, &ids, &mut projects).await
+}
+
+async fn attach_images<C: GenericClient>(client: &C, ids: &[Uuid], projects: &mut [&mut Project]) -> Result<(), AppError> {
+    let stmt = client
+        .prepare(&format!(
+            "SELECT {IMAGE_COLUMNS} FROM project_images 
+             WHERE project_id = ANY($1) 
+             ORDER BY position, id"
+        ))
+        .await?;
+    let mut by_project: HashMap<Uuid, Vec<ProjectImage>> = HashMap::new();
+    for row in client.query(&stmt, &[&ids]).await? {
+        by_project.entry(row.get("project_id")).or_default().push(image_from_row(&row));
+    }
+    for project in projects.iter_mut() {
+        project.images = by_project.remove(&project.id).unwrap_or_default();
+    }
+    Ok(())
+}
+
+async fn attach_tags<C: GenericClient>(client: &C, ids: &[Uuid], projects: &mut [&mut Project]) -> Result<(), AppError> {
+    let stmt = client
+        .prepare(
+            "SELECT pt.project_id, t.slug, t.name FROM project_tags pt 
+             JOIN tags t ON t.id = pt.tag_id 
+             WHERE pt.project_id = ANY($1) 
+             ORDER BY lower(t.name), t.slug"
+        )
+        .await?;
+    let mut by_project: HashMap<Uuid, Vec<Tag>> = HashMap::new();
+    for row in client.query(&stmt, &[&ids]).await? {
+        by_project
+            .entry(row.get("project_id"))
+            .or_default()
+            .push(Tag { slug: row.get("slug"), name: row.get("name") });
+    }
+    for project in projects.iter_mut() {
+        project.tags = by_project.remove(&project.id).unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// Replaces the tags of a project, creating missing tags. Names that slugify to the same
+/// tag are merged; an existing tag keeps its original display name.
+async fn set_tags<C: GenericClient>(client: &C, project_id: Uuid, names: &[String]) -> Result<Vec<Tag>, AppError> {
+    let mut by_slug: BTreeMap<String, String> = BTreeMap::new();
+    for name in names {
+        by_slug.entry(slugify(name)).or_insert_with(|| name.trim().to_string());
+    }
+    let slugs: Vec<String> = by_slug.keys().cloned().collect();
+    let display_names: Vec<String> = by_slug.into_values().collect();
+
+    client
+        .execute(
+            "INSERT INTO tags (slug, name) SELECT * FROM unnest($1::text[], $2::text[]) 
+             ON CONFLICT (slug) DO NOTHING",
+            &[&slugs, &display_names],
+        )
+        .await?;
+    client
+        .execute("DELETE FROM project_tags WHERE project_id = $1", &[&project_id])
+        .await?;
+    let rows = client
+        .query(
+            "WITH linked AS (
+                INSERT INTO project_tags (project_id, tag_id) 
+                SELECT $1, id FROM tags WHERE slug = ANY($2) 
+                RETURNING tag_id
+             ) 
+             SELECT t.slug, t.name FROM tags t JOIN linked l ON l.tag_id = t.id 
+             ORDER BY lower(t.name), t.slug",
+            &[&project_id, &slugs],
+        )
+        .await?;
+    Ok(rows.iter().map(|row| Tag { slug: row.get("slug"), name: row.get("name") }).collect())
+}
+
+/// `WHERE` conditions shared by `list` and `search`, using parameters `$first..$first+3`:
+/// category, tag slugs, whether all tags must match and status. Trashed projects never match.
+fn filter_clause(first: usize) -> String {
+    let (category, tags, match_all, status) = (first, first + 1, first + 2, first + 3);
+    format!(
+        "deleted_at IS NULL 
+         AND (${category}::text IS NULL OR category = ${category}) 
+         AND (${status}::text IS NULL OR status = ${status}) 
+         AND (${tags}::text[] IS NULL OR (
+            SELECT COUNT(*) FROM project_tags pt JOIN tags t ON t.id = pt.tag_id 
+            WHERE pt.project_id = projects.id AND t.slug = ANY(${tags})
+         ) >= CASE WHEN ${match_all} THEN cardinality(${tags}) ELSE 1 END)"
+    )
+}
+
+async fn insert_image<C: GenericClient>(
+    client: &C,
+    project_id: Uuid,
+    image: &NewProjectImageDTO,
+    position: i32,
+    is_cover: bool,
+) -> Result<ProjectImage, AppError> {
+    let row = client
+        .query_one(
+            &format!(
+                "INSERT INTO project_images (id, project_id, url, alt, caption, position, is_cover, width, height, blurhash, renditions) 
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) 
+                 RETURNING {IMAGE_COLUMNS}"
+            ),
+            &[
+                &Uuid::new_v4(),
+                &project_id,
+                &image.url,
+                &image.alt,
+                &image.caption,
+                &position,
+                &is_cover,
+                &image.width.map(|w| w as i32),
+                &image.height.map(|h| h as i32),
+                &image.blurhash,
+                &Json(&image.renditions),
+            ],
+        )
+        .await?;
+    Ok(image_from_row(&row))
+}
+
+/// Bumps `updated_at` of a project already locked with `lock_project`.
+async fn touch_project<C: GenericClient>(client: &C, id: Uuid) -> Result<(), AppError> {
+    client
+        .execute("UPDATE projects SET updated_at = CURRENT_TIMESTAMP WHERE id = $1", &[&id])
+        .await?;
+    Ok(())
+}
+
+/// Loads a project and locks its row until the transaction ends, so edits of the same
+/// project, and the revisions they record, happen one after the other.
+async fn lock_project<C: GenericClient>(client: &C, id: Uuid) -> Result<Project, AppError> {
+    let row = client
+        .query_opt(
+            &format!("SELECT {COLUMNS} FROM projects WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"),
+            &[&id],
+        )
+        .await?
+        .ok_or_else(|| not_found(id))?;
+    let mut project = from_row(row);
+    attach_images(client, &[id], &mut [&mut project]).await?;
+    attach_tags(client, &[id], &mut [&mut project]).await?;
+    Ok(project)
+}
+
+/// Keeps the content of `before` as the next revision when the edit changed it. Only
+/// called with the project row locked, so two edits can't take the same revision number.
+async fn record_revision<C: GenericClient>(client: &C, actor_id: Uuid, before: &Project, after: &Project) -> Result<(), AppError> {
+    let previous = ProjectContent::from(before);
+    if previous == ProjectContent::from(after) {
+        return Ok(());
+    }
+    client
+        .execute(
+            "INSERT INTO project_revisions (project_id, revision, content, actor_id) 
+             SELECT $1, COALESCE(MAX(revision), 0) + 1, $2, $3 FROM project_revisions WHERE project_id = $1",
+            &[&before.id, &Json(&previous), &actor_id],
+        )
+        .await?;
+    Ok(())
+}
+
+/// Reloads the project after an image edit and records the revision of the edit.
+async fn image_change<C: GenericClient>(client: &C, actor_id: Uuid, before: Project) -> Result<ProjectChange, AppError> {
+    let after = lock_project(client, before.id).await?;
+    record_revision(client, actor_id, &before, &after).await?;
+    Ok(ProjectChange { before, after })
+}
+
+/// Generated slugs are cut to this many characters before a collision suffix is added.
+const MAX_SLUG_BASE: usize = 100;
+
+fn slug_base(name: &str) -> String {
+    let slug: String = slugify(name).chars().take(MAX_SLUG_BASE).collect();
+    match slug.trim_end_matches('-') {
+        "" => "project".to_string(),
+        trimmed => trimmed.to_string(),
     }
 }
 
+/// Whether `slug` is `base` or `base-<n>`, i.e. still matches a project named like `base`.
+fn slug_matches_base(slug: &str, base: &str) -> bool {
+    match slug.strip_prefix(base) {
+        Some("") => true,
+        Some(rest) => rest
+            .strip_prefix('-')
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())),
+        None => false,
+    }
+}
+
+/// Picks a free slug for a project named `name`: the slugified name, or the first free
+/// `-2`, `-3`, ... suffix. Slugs in the history of other projects are taken as well so
+/// their redirects keep working; `project_id`'s own old slugs may be reclaimed.
+/// Allocation is serialized with a transaction-level advisory lock.
+async fn allocate_slug<C: GenericClient>(client: &C, name: &str, project_id: Option<Uuid>) -> Result<String, AppError> {
+    client
+        .execute("SELECT pg_advisory_xact_lock(hashtext('project_slugs'))", &[])
+        .await?;
+
+    let base = slug_base(name);
+    let rows = client
+        .query(
+            "SELECT slug FROM projects 
+             WHERE (slug = $1 OR slug LIKE $1 || '-%') AND id IS DISTINCT FROM $2 
+             UNION 
+             SELECT slug FROM project_slug_history 
+             WHERE (slug = $1 OR slug LIKE $1 || '-%') AND project_id IS DISTINCT FROM $2",
+            &[&base, &project_id],
+        )
+        .await?;
+    let taken: HashSet<String> = rows.iter().map(|row| row.get(0)).collect();
+
+    if !taken.contains(&base) {
+        return Ok(base);
+    }
+    Ok((2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("unbounded suffix range"))
+}
+
+/// Gives every project the slug `allocate_slug` would have, numbering duplicates by age.
+/// Run by migration 0010 after it filled the column with placeholders.
+pub async fn backfill_slugs<C: GenericClient>(client: &C) -> Result<(), AppError> {
+    let rows = client
+        .query("SELECT id, name FROM projects ORDER BY created_at, id", &[])
+        .await?;
+    let mut taken = HashSet::new();
+    for row in rows {
+        let id: Uuid = row.get("id");
+        let base = slug_base(row.get("name"));
+        let slug = std::iter::once(base.clone())
+            .chain((2..).map(|n| format!("{}-{}", base, n)))
+            .find(|candidate| !taken.contains(candidate))
+            .expect("unbounded suffix range");
+        client
+            .execute("UPDATE projects SET slug = $1 WHERE id = $2", &[&slug, &id])
+            .await?;
+        taken.insert(slug);
+    }
+    Ok(())
+}
+
+fn sort_column(field: ProjectSortField) -> &'static str {
+    match field {
+        ProjectSortField::CreatedAt => "created_at",
+        ProjectSortField::UpdatedAt => "updated_at",
+        ProjectSortField::Name => "name",
+        ProjectSortField::Category => "category",
+    }
+}
+
+fn not_found(id: Uuid) -> AppError {
+    AppError::NotFound(format!("Project {} not found", id))
+}
+
+/// `projects.category` references `categories.slug`.
+fn unknown_category(err: tokio_postgres::Error) -> AppError {
+    if err.code() == Some(&SqlState::FOREIGN_KEY_VIOLATION) {
+        return AppError::InvalidFields(FieldErrors::from([(
+            "category".to_string(),
+            vec!["must be the slug of an existing category".to_string()],
+        )]));
+    }
+    AppError::from(err)
+}
+
+fn image_not_found(project_id: Uuid, image_id: Uuid) -> AppError {
+    AppError::NotFound(format!("Image {} not found in project {}", image_id, project_id))
+}
+
+/// Applies `dto` on top of `current`. Shared by `update` and `restore_revision`.
+async fn apply_update<C: GenericClient>(client: &C, current: Project, dto: UpdateProjectDTO) -> Result<Project, AppError> {
+    let id = current.id;
+    let renamed = dto.name.as_ref().is_some_and(|name| *name != current.name);
+    let name = dto.name.unwrap_or(current.name);
+    let description = dto.description.unwrap_or(current.description);
+    let category = dto.category.unwrap_or(current.category);
+    let status = dto.status.unwrap_or(current.status);
+    let publish_at = dto.publish_at.unwrap_or(current.publish_at);
+
+    // A rename moves the project to a new slug; the old one keeps redirecting. Other edits
+    // never touch the slug, even one that no longer matches the name.
+    let slug = if !renamed || slug_matches_base(&current.slug, &slug_base(&name)) {
+        current.slug
+    } else {
+        let slug = allocate_slug(client, &name, Some(id)).await?;
+        client
+            .execute(
+                "DELETE FROM project_slug_history WHERE slug = $1 AND project_id = $2",
+                &[&slug, &id],
+            )
+            .await?;
+        client
+            .execute(
+                "INSERT INTO project_slug_history (slug, project_id) VALUES ($1, $2)",
+                &[&current.slug, &id],
+            )
+            .await?;
+        slug
+    };
+
+    let row = client
+        .query_opt(
+            &format!(
+                "UPDATE projects SET name = $1, slug = $2, description = $3, category = $4, 
+                 status = $5, publish_at = $6, updated_at = CURRENT_TIMESTAMP 
+                 WHERE id = $7 AND deleted_at IS NULL 
+                 RETURNING {COLUMNS}"
+            ),
+            &[&name, &slug, &description, &category, &status.as_str(), &publish_at, &id],
+        )
+        .await
+        .map_err(unknown_category)?
+        .ok_or_else(|| not_found(id))?;
+
+    let mut project = from_row(row);
+    project.images = current.images;
+    project.tags = match dto.tags {
+        Some(tags) => set_tags(client, id, &tags).await?,
+        None => current.tags,
+    };
+
+    Ok(project)
+}
+
 impl ProjectPostgresRepo {
-    pub async fn add(&self, dto: CreateProjectDTO) -> Result<Project, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+    pub async fn add(&self, dto: CreateProjectDTO) -> Result<Project, AppError> {
+        let mut client = self.pool.get().await?;
+        let tx = client.transaction().await?;
         let id = Uuid::new_v4();
-        
-        let stmt = client
-            .prepare(
-                "INSERT INTO projects (id, name, description, images, category) 
-                 VALUES ($1, $2, $3, $4, $5) 
-                 RETURNING id, name, description, images, category, created_at, updated_at"
-            )
-            .await
-            .map_err(|e| e.to_string())?;
-        
-        let row = client
+        let slug = allocate_slug(&*tx, &dto.name, None).await?;
+        let status = dto.status.unwrap_or(ProjectStatus::Draft);
+
+        let row = tx
             .query_one(
-                &stmt, 
-                &[
-                    &id, 
-                    &dto.name, 
-                    &dto.description, 
-                    &dto.images,
-                    &dto.category
-                ]
+                &format!(
+                    "INSERT INTO projects (id, name, slug, description, category, status, publish_at) 
+                     VALUES ($1, $2, $3, $4, $5, $6, $7) 
+                     RETURNING {COLUMNS}"
+                ),
+                &[&id, &dto.name, &slug, &dto.description, &dto.category, &status.as_str(), &dto.publish_at],
             )
             .await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(from_row(row))
+            .map_err(unknown_category)?;
+        let mut project = from_row(row);
+
+        let cover = dto.images.iter().position(|image| image.is_cover).unwrap_or(0);
+        for (position, image) in dto.images.iter().enumerate() {
+            project.images.push(insert_image(&*tx, id, image, position as i32, position == cover).await?);
+        }
+        project.tags = set_tags(&*tx, id, &dto.tags).await?;
+
+        tx.commit().await?;
+        Ok(project)
+    }
+
+    pub async fn list(&self, query: &ProjectListQuery, page: PageRequest) -> Result<Page<Project>, AppError> {
+        let client = self.pool.get().await?;
+        let column = sort_column(query.sort.unwrap_or(ProjectSortField::CreatedAt));
+        let order = query.order.unwrap_or(SortOrder::Desc).as_sql();
+
+        let tags = query.tag_slugs();
+        let match_all = query.tag_match.unwrap_or(TagMatch::All) == TagMatch::All;
+        let status = query.status.map(|status| status.as_str());
+        let filter = filter_clause(1);
+
+        let count_stmt = client
+            .prepare(&format!("SELECT COUNT(*) FROM projects WHERE {filter}"))
+            .await?;
+        let total: i64 = client.query_one(&count_stmt, &[&query.category, &tags, &match_all, &status]).await?.get(0);
+
+        let stmt = client
+            .prepare(&format!(
+                "SELECT {COLUMNS} FROM projects 
+                 WHERE {filter} 
+                 ORDER BY {column} {order}, id {order} 
+                 LIMIT $5 OFFSET $6"
+            ))
+            .await?;
+        let rows = client.query(&stmt, &[&query.category, &tags, &match_all, &status, &page.limit, &page.offset]).await?;
+        let mut items: Vec<Project> = rows.into_iter().map(from_row).collect();
+        attach_details(&client, &mut items).await?;
+        Ok(Page { items, total })
     }
 
-    pub async fn get_all(&self) -> Result<Vec<Project>, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+    /// Ranked full-text search. Results are ordered by relevance unless `sort` is given.
+    pub async fn search(&self, term: &str, query: &ProjectListQuery, page: PageRequest) -> Result<Page<SearchHit<Project>>, AppError> {
+        let client = self.pool.get().await?;
+        let order_by = match query.sort {
+            Some(field) => {
+                let order = query.order.unwrap_or(SortOrder::Desc).as_sql();
+                format!("{} {order}, id {order}", sort_column(field))
+            }
+            None => "rank DESC, created_at DESC, id DESC".to_string(),
+        };
+
+        let tags = query.tag_slugs();
+        let match_all = query.tag_match.unwrap_or(TagMatch::All) == TagMatch::All;
+        let status = query.status.map(|status| status.as_str());
+        let filter = filter_clause(2);
+
+        let count_stmt = client
+            .prepare(&format!(
+                "SELECT COUNT(*) FROM projects 
+                 WHERE search_vector @@ websearch_to_tsquery('simple', $1) 
+                 AND {filter}"
+            ))
+            .await?;
+        let total: i64 = client.query_one(&count_stmt, &[&term, &query.category, &tags, &match_all, &status]).await?.get(0);
+
+        let name_headline = headline_sql("name");
+        let description_headline = headline_sql("description");
         let stmt = client
-            .prepare("SELECT id, name, description, images, category, created_at, updated_at FROM projects ORDER BY created_at DESC")
-            .await
-            .map_err(|e| e.to_string())?;
-        let rows = client.query(&stmt, &[]).await.map_err(|e| e.to_string())?;
-        Ok(rows.into_iter().map(from_row).collect())
+            .prepare(&format!(
+                "SELECT {COLUMNS}, 
+                        ts_rank_cd(search_vector, tsq) AS rank, 
+                        {name_headline} AS name_highlight, 
+                        {description_headline} AS description_highlight 
+                 FROM projects, websearch_to_tsquery('simple', $1) AS tsq 
+                 WHERE search_vector @@ tsq 
+                 AND {filter} 
+                 ORDER BY {order_by} 
+                 LIMIT $6 OFFSET $7"
+            ))
+            .await?;
+        let rows = client.query(&stmt, &[&term, &query.category, &tags, &match_all, &status, &page.limit, &page.offset]).await?;
+        let mut items: Vec<SearchHit<Project>> = rows
+            .into_iter()
+            .map(|row| {
+                let rank: f32 = row.get("rank");
+                let highlights = BTreeMap::from([
+                    ("name", row.get("name_highlight")),
+                    ("description", row.get("description_highlight")),
+                ]);
+                SearchHit { item: from_row(row), rank, highlights }
+            })
+            .collect();
+        attach_details(&client, items.iter_mut().map(|hit| &mut hit.item)).await?;
+        Ok(Page { items, total })
     }
 
-    pub async fn get_by_category(&self, category: String) -> Result<Vec<Project>, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Project, AppError> {
+        let client = self.pool.get().await?;
         let stmt = client
-            .prepare("SELECT id, name, description, images, category, created_at, updated_at FROM projects WHERE category = $1 ORDER BY created_at DESC")
-            .await
-            .map_err(|e| e.to_string())?;
-        let rows = client.query(&stmt, &[&category]).await.map_err(|e| e.to_string())?;
-        Ok(rows.into_iter().map(from_row).collect())
+            .prepare(&format!("SELECT {COLUMNS} FROM projects WHERE id = $1 AND deleted_at IS NULL"))
+            .await?;
+        let row = client
+            .query_opt(&stmt, &[&id])
+            .await?
+            .ok_or_else(|| not_found(id))?;
+        let mut project = from_row(row);
+        attach_details(&client, [&mut project]).await?;
+        Ok(project)
     }
 
-    pub async fn get_by_id(&self, id: Uuid) -> Result<Project, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+    /// Finds a project by its current slug, or by a slug it had before being renamed.
+    pub async fn get_by_slug(&self, slug: &str) -> Result<SlugLookup, AppError> {
+        let client = self.pool.get().await?;
         let stmt = client
-            .prepare("SELECT id, name, description, images, category, created_at, updated_at FROM projects WHERE id = $1")
-            .await
-            .map_err(|e| e.to_string())?;
+            .prepare(&format!("SELECT {COLUMNS} FROM projects WHERE slug = $1 AND deleted_at IS NULL"))
+            .await?;
+        if let Some(row) = client.query_opt(&stmt, &[&slug]).await? {
+            let mut project = from_row(row);
+            attach_details(&client, [&mut project]).await?;
+            return Ok(SlugLookup::Current(Box::new(project)));
+        }
+
+        let stmt = client
+            .prepare(&format!(
+                "SELECT {COLUMNS} FROM projects 
+                 WHERE id = (SELECT project_id FROM project_slug_history WHERE slug = $1) 
+                 AND deleted_at IS NULL"
+            ))
+            .await?;
+        match client.query_opt(&stmt, &[&slug]).await? {
+            Some(row) => {
+                let mut project = from_row(row);
+                attach_details(&client, [&mut project]).await?;
+                Ok(SlugLookup::Moved(Box::new(project)))
+            }
+            None => Err(AppError::NotFound(format!("Project '{}' not found", slug))),
+        }
+    }
+
+    /// Applies `dto` and records the replaced content as a revision, in one transaction.
+    pub async fn update(&self, actor_id: Uuid, id: Uuid, dto: UpdateProjectDTO) -> Result<ProjectChange, AppError> {
+        let mut client = self.pool.get().await?;
+        let tx = client.transaction().await?;
+        let before = lock_project(&*tx, id).await?;
+        let after = apply_update(&*tx, before.clone(), dto).await?;
+        record_revision(&*tx, actor_id, &before, &after).await?;
+        tx.commit().await?;
+        Ok(ProjectChange { before, after })
+    }
+
+    /// Puts back the content of a revision: name, description, category, tags and images.
+    /// The content being replaced is recorded as a revision itself.
+    pub async fn restore_content(&self, actor_id: Uuid, id: Uuid, content: ProjectContent) -> Result<ProjectChange, AppError> {
+        let mut client = self.pool.get().await?;
+        let tx = client.transaction().await?;
+        let before = lock_project(&*tx, id).await?;
+
+        let dto = UpdateProjectDTO {
+            name: Some(content.name),
+            description: Some(content.description),
+            category: Some(content.category),
+            tags: Some(content.tags.into_iter().map(|tag| tag.name).collect()),
+            status: None,
+            publish_at: None,
+        };
+        let mut project = apply_update(&*tx, before.clone(), dto).await?;
+
+        tx.execute("DELETE FROM project_images WHERE project_id = $1", &[&id]).await?;
+        project.images.clear();
+        for (position, image) in content.images.into_iter().enumerate() {
+            let is_cover = image.is_cover;
+            let image = NewProjectImageDTO {
+                url: image.url,
+                alt: image.alt,
+                caption: image.caption,
+                is_cover,
+                width: image.width,
+                height: image.height,
+                blurhash: image.blurhash,
+                renditions: image.renditions,
+            };
+            project.images.push(insert_image(&*tx, id, &image, position as i32, is_cover).await?);
+        }
+        record_revision(&*tx, actor_id, &before, &project).await?;
+
+        tx.commit().await?;
+        Ok(ProjectChange { before, after: project })
+    }
+
+    /// Newest revision first.
+    pub async fn revisions(&self, project_id: Uuid) -> Result<Vec<ProjectRevision>, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(&format!(
+                "SELECT {REVISION_COLUMNS} FROM project_revisions r 
+                 LEFT JOIN users u ON u.id = r.actor_id 
+                 WHERE r.project_id = $1 
+                 ORDER BY r.revision DESC"
+            ))
+            .await?;
+        let rows = client.query(&stmt, &[&project_id]).await?;
+        Ok(rows.iter().map(revision_from_row).collect())
+    }
+
+    pub async fn revision(&self, project_id: Uuid, revision: i32) -> Result<ProjectRevision, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(&format!(
+                "SELECT {REVISION_COLUMNS} FROM project_revisions r 
+                 LEFT JOIN users u ON u.id = r.actor_id 
+                 WHERE r.project_id = $1 AND r.revision = $2"
+            ))
+            .await?;
         let row = client
-            .query_one(&stmt, &[&id])
-            .await
-            .map_err(|e| e.to_string())?;
-        Ok(from_row(row))
+            .query_opt(&stmt, &[&project_id, &revision])
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Revision {} of project {} not found", revision, project_id)))?;
+        Ok(revision_from_row(&row))
     }
 
-    pub async fn update(&self, id: Uuid, dto: UpdateProjectDTO) -> Result<Project, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
-        let current = self.get_by_id(id).await?;
-        
-        let name = dto.name.unwrap_or(current.name);
-        let description = dto.description.unwrap_or(current.description);
-        let images = dto.images.unwrap_or(current.images);
-        let category = dto.category.unwrap_or(current.category);
+    /// Publishes drafts whose `publish_at` has passed and returns their ids.
+    pub async fn publish_scheduled(&self) -> Result<Vec<Uuid>, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(
+                "UPDATE projects SET status = 'published', updated_at = CURRENT_TIMESTAMP 
+                 WHERE status = 'draft' AND publish_at <= CURRENT_TIMESTAMP AND deleted_at IS NULL 
+                 RETURNING id"
+            )
+            .await?;
+        let rows = client.query(&stmt, &[]).await?;
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
 
+    /// Tags used by at least one published project, most used first.
+    pub async fn tag_cloud(&self) -> Result<Vec<TagCount>, AppError> {
+        let client = self.pool.get().await?;
         let stmt = client
             .prepare(
-                "UPDATE projects SET name = $1, description = $2, images = $3, category = $4, updated_at = CURRENT_TIMESTAMP 
-                 WHERE id = $5 
-                 RETURNING id, name, description, images, category, created_at, updated_at"
+                "SELECT t.slug, t.name, COUNT(*) AS project_count FROM tags t 
+                 JOIN project_tags pt ON pt.tag_id = t.id 
+                 JOIN projects p ON p.id = pt.project_id 
+                 WHERE p.status = 'published' AND p.deleted_at IS NULL 
+                 GROUP BY t.id 
+                 ORDER BY project_count DESC, lower(t.name)"
             )
-            .await
-            .map_err(|e| e.to_string())?;
-        
-        let row = client
-            .query_one(&stmt, &[&name, &description, &images, &category, &id])
-            .await
-            .map_err(|e| e.to_string())?;
-        
-        Ok(from_row(row))
+            .await?;
+        let rows = client.query(&stmt, &[]).await?;
+        Ok(rows
+            .iter()
+            .map(|row| TagCount {
+                tag: Tag { slug: row.get("slug"), name: row.get("name") },
+                project_count: row.get("project_count"),
+            })
+            .collect())
     }
 
-    pub async fn delete(&self, id: Uuid) -> Result<u64, String> {
-        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+    /// Moves the project to the trash; it is removed for good by `purge`.
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        let client = self.pool.get().await?;
         let stmt = client
-            .prepare("DELETE FROM projects WHERE id = $1")
-            .await
-            .map_err(|e| e.to_string())?;
+            .prepare("UPDATE projects SET deleted_at = CURRENT_TIMESTAMP WHERE id = $1 AND deleted_at IS NULL")
+            .await?;
         let res = client
             .execute(&stmt, &[&id as &(dyn ToSql + Sync)])
-            .await
-            .map_err(|e| e.to_string())?;
-        Ok(res)
+            .await?;
+        if res == 0 {
+            return Err(not_found(id));
+        }
+        Ok(())
+    }
+
+    /// Trashed projects, most recently deleted first.
+    pub async fn trash(&self) -> Result<Vec<Project>, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(&format!(
+                "SELECT {COLUMNS} FROM projects WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id"
+            ))
+            .await?;
+        let mut items: Vec<Project> = client.query(&stmt, &[]).await?.into_iter().map(from_row).collect();
+        attach_details(&client, &mut items).await?;
+        Ok(items)
+    }
+
+    pub async fn restore(&self, id: Uuid) -> Result<Project, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare(&format!(
+                "UPDATE projects SET deleted_at = NULL, updated_at = CURRENT_TIMESTAMP 
+                 WHERE id = $1 AND deleted_at IS NOT NULL 
+                 RETURNING {COLUMNS}"
+            ))
+            .await?;
+        let row = client
+            .query_opt(&stmt, &[&id])
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Project {} is not in the trash", id)))?;
+        let mut project = from_row(row);
+        attach_details(&client, [&mut project]).await?;
+        Ok(project)
+    }
+
+    /// Permanently deletes projects trashed before `cutoff`. They are returned together with
+    /// the stored files that were only referenced by them (their images, renditions and
+    /// the images of their revisions), so those files can be removed as well.
+    pub async fn purge(&self, cutoff: DateTime<Utc>) -> Result<(Vec<Project>, Vec<String>), AppError> {
+        let mut client = self.pool.get().await?;
+        let tx = client.transaction().await?;
+        let rows = tx
+            .query(
+                &format!("SELECT {COLUMNS} FROM projects WHERE deleted_at < $1 FOR UPDATE"),
+                &[&cutoff],
+            )
+            .await?;
+        let mut purged: Vec<Project> = rows.into_iter().map(from_row).collect();
+        if purged.is_empty() {
+            return Ok((purged, Vec::new()));
+        }
+        // Read the images before the DELETE cascades to them
+        let ids: Vec<Uuid> = purged.iter().map(|p| p.id).collect();
+        let mut refs: Vec<&mut Project> = purged.iter_mut().collect();
+        attach_images(&*tx, &ids, &mut refs).await?;
+
+        let mut seen = HashSet::new();
+        let mut urls: Vec<String> = purged
+            .iter()
+            .flat_map(|p| &p.images)
+            .flat_map(ProjectImage::asset_urls)
+            .filter(|url| seen.insert(url.clone()))
+            .collect();
+        for row in tx
+            .query("SELECT content FROM project_revisions WHERE project_id = ANY($1)", &[&ids])
+            .await?
+        {
+            let Json(content): Json<ProjectContent> = row.get("content");
+            urls.extend(
+                content
+                    .images
+                    .iter()
+                    .flat_map(ProjectImage::asset_urls)
+                    .filter(|url| seen.insert(url.clone())),
+            );
+        }
+
+        tx.execute("DELETE FROM projects WHERE id = ANY($1)", &[&ids]).await?;
+
+        // A file can also be referenced by another project (e.g. an image URL that was
+        // copied over), either by its images or by one of its revisions; those are kept
+        let kept: HashSet<String> = tx
+            .query(
+                "SELECT u.url FROM unnest($1::text[]) AS u(url) 
+                 WHERE EXISTS (
+                     SELECT 1 FROM project_images i 
+                     WHERE i.url = u.url 
+                        OR jsonb_path_exists(i.renditions, '$.*.url ? (@ == $u)', jsonb_build_object('u', u.url))
+                 ) OR EXISTS (
+                     SELECT 1 FROM project_revisions r 
+                     WHERE jsonb_path_exists(r.content, '$.images[*] ? (@.url == $u || @.renditions.*.url == $u)', jsonb_build_object('u', u.url))
+                 )",
+                &[&urls],
+            )
+            .await?
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+        urls.retain(|url| !kept.contains(url));
+
+        tx.commit().await?;
+        Ok((purged, urls))
+    }
+
+    /// Appends an image. It becomes the cover when requested or when the project has none.
+    pub async fn add_image(&self, actor_id: Uuid, project_id: Uuid, dto: NewProjectImageDTO) -> Result<(ProjectImage, ProjectChange), AppError> {
+        let mut client = self.pool.get().await?;
+        let tx = client.transaction().await?;
+        let before = lock_project(&*tx, project_id).await?;
+        touch_project(&*tx, project_id).await?;
+
+        let row = tx
+            .query_one(
+                "SELECT COALESCE(MAX(position) + 1, 0) AS next_position, bool_or(is_cover) IS TRUE AS has_cover 
+                 FROM project_images WHERE project_id = $1",
+                &[&project_id],
+            )
+            .await?;
+        let position: i32 = row.get("next_position");
+        let has_cover: bool = row.get("has_cover");
+
+        if dto.is_cover {
+            tx.execute(
+                "UPDATE project_images SET is_cover = FALSE WHERE project_id = $1 AND is_cover",
+                &[&project_id],
+            )
+            .await?;
+        }
+        let image = insert_image(&*tx, project_id, &dto, position, dto.is_cover || !has_cover).await?;
+        let change = image_change(&*tx, actor_id, before).await?;
+
+        tx.commit().await?;
+        Ok((image, change))
+    }
+
+    pub async fn update_image(&self, actor_id: Uuid, project_id: Uuid, image_id: Uuid, dto: UpdateProjectImageDTO) -> Result<(ProjectImage, ProjectChange), AppError> {
+        let mut client = self.pool.get().await?;
+        let tx = client.transaction().await?;
+        let before = lock_project(&*tx, project_id).await?;
+        touch_project(&*tx, project_id).await?;
+
+        let make_cover = dto.is_cover.unwrap_or(false);
+        if make_cover {
+            tx.execute(
+                "UPDATE project_images SET is_cover = FALSE WHERE project_id = $1 AND is_cover AND id <> $2",
+                &[&project_id, &image_id],
+            )
+            .await?;
+        }
+
+        let row = tx
+            .query_opt(
+                &format!(
+                    "UPDATE project_images SET 
+                        alt = CASE WHEN $3::text IS NULL THEN alt ELSE NULLIF($3, '') END, 
+                        caption = CASE WHEN $4::text IS NULL THEN caption ELSE NULLIF($4, '') END, 
+                        is_cover = is_cover OR $5 
+                     WHERE project_id = $1 AND id = $2 
+                     RETURNING {IMAGE_COLUMNS}"
+                ),
+                &[&project_id, &image_id, &dto.alt, &dto.caption, &make_cover],
+            )
+            .await?
+            .ok_or_else(|| image_not_found(project_id, image_id))?;
+        let change = image_change(&*tx, actor_id, before).await?;
+
+        tx.commit().await?;
+        Ok((image_from_row(&row), change))
+    }
+
+    pub async fn reorder_images(&self, actor_id: Uuid, project_id: Uuid, dto: ReorderImagesDTO) -> Result<ProjectChange, AppError> {
+        let mut client = self.pool.get().await?;
+        let tx = client.transaction().await?;
+        let before = lock_project(&*tx, project_id).await?;
+        touch_project(&*tx, project_id).await?;
+
+        let current: HashSet<Uuid> = before.images.iter().map(|image| image.id).collect();
+        let requested: HashSet<Uuid> = dto.image_ids.iter().copied().collect();
+        if requested.len() != dto.image_ids.len() || requested != current {
+            return Err(AppError::Validation(
+                "image_ids must list every image of the project exactly once".to_string(),
+            ));
+        }
+
+        tx.execute(
+            "UPDATE project_images SET position = new_order.ord - 1 
+             FROM unnest($2::uuid[]) WITH ORDINALITY AS new_order(id, ord) 
+             WHERE project_images.project_id = $1 AND project_images.id = new_order.id",
+            &[&project_id, &dto.image_ids],
+        )
+        .await?;
+
+        let change = image_change(&*tx, actor_id, before).await?;
+
+        tx.commit().await?;
+        Ok(change)
+    }
+
+    /// Removes an image and returns it. The last image of a project cannot be removed;
+    /// removing the cover promotes the next image.
+    pub async fn remove_image(&self, actor_id: Uuid, project_id: Uuid, image_id: Uuid) -> Result<(ProjectImage, ProjectChange), AppError> {
+        let mut client = self.pool.get().await?;
+        let tx = client.transaction().await?;
+        let before = lock_project(&*tx, project_id).await?;
+        touch_project(&*tx, project_id).await?;
+
+        let row = tx
+            .query_opt(
+                &format!("DELETE FROM project_images WHERE project_id = $1 AND id = $2 RETURNING {IMAGE_COLUMNS}"),
+                &[&project_id, &image_id],
+            )
+            .await?
+            .ok_or_else(|| image_not_found(project_id, image_id))?;
+        let removed = image_from_row(&row);
+
+        let remaining: i64 = tx
+            .query_one("SELECT COUNT(*) FROM project_images WHERE project_id = $1", &[&project_id])
+            .await?
+            .get(0);
+        if remaining == 0 {
+            return Err(AppError::Validation("A project must keep at least one image".to_string()));
+        }
+
+        // Close the gap in positions and hand the cover to the first image if needed
+        tx.execute(
+            "UPDATE project_images SET 
+                position = ordered.rn - 1, 
+                is_cover = project_images.is_cover OR ($2 AND ordered.rn = 1) 
+             FROM (
+                SELECT id, row_number() OVER (ORDER BY position, id) AS rn 
+                FROM project_images WHERE project_id = $1
+             ) AS ordered 
+             WHERE project_images.id = ordered.id",
+            &[&project_id, &removed.is_cover],
+        )
+        .await?;
+
+        let change = image_change(&*tx, actor_id, before).await?;
+
+        tx.commit().await?;
+        Ok((removed, change))
     }
 }
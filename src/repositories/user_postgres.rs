@@ -0,0 +1,63 @@
+use crate::models::user::{Role, User};
+use crate::errors::AppError;
+use deadpool_postgres::Pool;
+use uuid::Uuid;
+use tokio_postgres::Row;
+
+pub struct UserPostgresRepo {
+    pub pool: Pool,
+}
+
+fn from_row(row: &Row) -> Result<User, AppError> {
+    let role: String = row.get("role");
+    Ok(User {
+        id: row.get::<_, Uuid>("id"),
+        email: row.get("email"),
+        role: role.parse().map_err(AppError::Internal)?,
+    })
+}
+
+impl UserPostgresRepo {
+    pub async fn add(&self, email: &str, password_hash: &str, role: Role) -> Result<User, AppError> {
+        let client = self.pool.get().await?;
+        let id = Uuid::new_v4();
+        let stmt = client
+            .prepare("INSERT INTO users (id, email, password_hash, role) VALUES ($1, $2, $3, $4) RETURNING id, email, role")
+            .await?;
+        let row = client
+            .query_one(&stmt, &[&id, &email, &password_hash, &role.as_str()])
+            .await
+            .map_err(|e| match AppError::from(e) {
+                AppError::Conflict(_) => AppError::Conflict("A user with this email already exists".to_string()),
+                other => other,
+            })?;
+        from_row(&row)
+    }
+
+    /// Returns the user together with its stored password hash.
+    pub async fn find_by_email(&self, email: &str) -> Result<Option<(User, String)>, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare("SELECT id, email, role, password_hash FROM users WHERE email = $1")
+            .await?;
+        let row = client
+            .query_opt(&stmt, &[&email])
+            .await?;
+        match row {
+            Some(row) => Ok(Some((from_row(&row)?, row.get("password_hash")))),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<User, AppError> {
+        let client = self.pool.get().await?;
+        let stmt = client
+            .prepare("SELECT id, email, role FROM users WHERE id = $1")
+            .await?;
+        let row = client
+            .query_opt(&stmt, &[&id])
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", id)))?;
+        from_row(&row)
+    }
+}
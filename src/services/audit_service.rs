@@ -0,0 +1,75 @@
+use crate::dtos::audit_dto::AuditQuery;
+use crate::dtos::pagination_dto::PageRequest;
+use crate::models::audit::{AuditAction, AuditEntity, AuditEntry};
+use crate::models::pagination::Page;
+use crate::repositories::audit_postgres::AuditPostgresRepo;
+use crate::errors::AppError;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+/// Fields that change on every write or only exist in responses; they are left out of diffs.
+const IGNORED_FIELDS: &[&str] = &["updated_at", "deleted_at", "created_at_display", "updated_at_display"];
+
+pub struct AuditService {
+    pub pg_repo: AuditPostgresRepo,
+}
+
+impl AuditService {
+    pub async fn list(&self, query: &AuditQuery, page: PageRequest) -> Result<Page<AuditEntry>, AppError> {
+        self.pg_repo.list(query, page).await
+    }
+
+    /// Records the fields that differ between two states of an entity. `before` is `None`
+    /// for creations and `after` for removals. Updates that change nothing are skipped.
+    pub async fn record<T: Serialize>(
+        &self,
+        actor: Option<Uuid>,
+        action: AuditAction,
+        entity: AuditEntity,
+        entity_id: Uuid,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) {
+        let snapshot = |state: Option<&T>| state.and_then(|s| serde_json::to_value(s).ok()).unwrap_or(Value::Null);
+        let changes = diff(&snapshot(before), &snapshot(after));
+        if action == AuditAction::Update && changes.as_object().is_some_and(Map::is_empty) {
+            return;
+        }
+        self.record_changes(actor, action, entity, entity_id, changes).await;
+    }
+
+    /// Best effort: the change itself is already committed, so a failure is only logged.
+    pub async fn record_changes(
+        &self,
+        actor: Option<Uuid>,
+        action: AuditAction,
+        entity: AuditEntity,
+        entity_id: Uuid,
+        changes: Value,
+    ) {
+        if let Err(e) = self.pg_repo.add(actor, action, entity, entity_id, &changes).await {
+            eprintln!("⚠️  Failed to write audit entry ({} {} {}): {}", action, entity, entity_id, e);
+        }
+    }
+}
+
+/// `{ "<field>": { "before": ..., "after": ... } }` for every top-level field that differs.
+pub fn diff(before: &Value, after: &Value) -> Value {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+
+    let fields: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    let changes: Map<String, Value> = fields
+        .into_iter()
+        .filter(|field| !IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|field| {
+            let old = before.get(field).unwrap_or(&Value::Null);
+            let new = after.get(field).unwrap_or(&Value::Null);
+            (old != new).then(|| (field.clone(), json!({ "before": old, "after": new })))
+        })
+        .collect();
+    Value::Object(changes)
+}
@@ -0,0 +1,137 @@
+use crate::models::user::{Role, User};
+use crate::repositories::user_postgres::UserPostgresRepo;
+use crate::errors::AppError;
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Argon2id hash with the default parameters used by `hash_password`, checked against for
+/// unknown emails so they take as long as wrong passwords.
+const DUMMY_HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$5Arfljs9DzsoUqK6731Gzg$W3tejCMXtO391as5FO+u/wIMeBaBCAiHoLCNj80YZJg";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: Uuid,
+    pub email: String,
+    pub role: Role,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+pub struct AuthService {
+    pub pg_repo: UserPostgresRepo,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    token_ttl_secs: i64,
+}
+
+impl AuthService {
+    pub fn new(pg_repo: UserPostgresRepo, secret: &str, token_ttl_secs: i64) -> Self {
+        Self {
+            pg_repo,
+            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            token_ttl_secs,
+        }
+    }
+
+    pub fn token_ttl_secs(&self) -> i64 {
+        self.token_ttl_secs
+    }
+
+    pub async fn login(&self, email: &str, password: &str) -> Result<(User, String), AppError> {
+        let invalid = || AppError::Unauthorized("Invalid email or password".to_string());
+        let Some((user, password_hash)) = self.pg_repo.find_by_email(email).await? else {
+            // Unknown emails cost a full verification too, so timing doesn't reveal accounts
+            verify_password(password.to_string(), DUMMY_HASH.to_string()).await?;
+            return Err(invalid());
+        };
+        if !verify_password(password.to_string(), password_hash).await? {
+            return Err(invalid());
+        }
+        let token = self.issue_token(&user)?;
+        Ok((user, token))
+    }
+
+    pub async fn create_user(&self, email: &str, password: &str, role: Role) -> Result<User, AppError> {
+        let password_hash = hash_password(password.to_string()).await?;
+        self.pg_repo.add(email, &password_hash, role).await
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<User, AppError> {
+        self.pg_repo.get_by_id(id).await
+    }
+
+    /// Creates the initial admin account on first boot so there is always someone able to log in.
+    pub async fn ensure_admin(&self, email: &str, password: &str) -> Result<(), AppError> {
+        if self.pg_repo.find_by_email(email).await?.is_none() {
+            self.create_user(email, password, Role::Admin).await?;
+            println!("👤 Created admin user {}", email);
+        }
+        Ok(())
+    }
+
+    pub fn issue_token(&self, user: &User) -> Result<String, AppError> {
+        let now = chrono::Utc::now().timestamp();
+        let claims = Claims {
+            sub: user.id,
+            email: user.email.clone(),
+            role: user.role,
+            iat: now,
+            exp: now + self.token_ttl_secs,
+        };
+        encode(&Header::default(), &claims, &self.encoding_key).map_err(|e| AppError::Internal(e.to_string()))
+    }
+
+    pub fn verify_token(&self, token: &str) -> Result<Claims, AppError> {
+        decode::<Claims>(token, &self.decoding_key, &Validation::default())
+            .map(|data| data.claims)
+            .map_err(|e| AppError::Unauthorized(format!("Invalid token: {}", e)))
+    }
+}
+
+/// Argon2 is deliberately slow, so hashing and verifying run on the blocking thread pool
+/// instead of stalling the async worker.
+async fn hash_password(password: String) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| AppError::Internal(e.to_string()))
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Password hashing task failed: {}", e)))?
+}
+
+async fn verify_password(password: String, password_hash: String) -> Result<bool, AppError> {
+    tokio::task::spawn_blocking(move || {
+        PasswordHash::new(&password_hash)
+            .map(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+            .unwrap_or(false)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Password verification task failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn passwords_verify_against_their_hash_only() {
+        let hash = hash_password("correct horse".to_string()).await.unwrap();
+        assert!(verify_password("correct horse".to_string(), hash.clone()).await.unwrap());
+        assert!(!verify_password("wrong horse".to_string(), hash).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn dummy_hash_costs_as_much_as_a_real_one() {
+        let hash = hash_password("anything".to_string()).await.unwrap();
+        let params = |hash: &str| PasswordHash::new(hash).unwrap().params.to_string();
+        assert_eq!(params(DUMMY_HASH), params(&hash));
+        assert!(!verify_password("anything".to_string(), DUMMY_HASH.to_string()).await.unwrap());
+    }
+}
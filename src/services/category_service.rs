@@ -0,0 +1,31 @@
+use crate::dtos::category_dto::{CreateCategoryDTO, UpdateCategoryDTO};
+use crate::models::category::Category;
+use crate::repositories::category_postgres::CategoryPostgresRepo;
+use crate::errors::AppError;
+use uuid::Uuid;
+
+pub struct CategoryService {
+    pub pg_repo: CategoryPostgresRepo,
+}
+
+impl CategoryService {
+    pub async fn add(&self, dto: CreateCategoryDTO) -> Result<Category, AppError> {
+        self.pg_repo.add(dto).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<Category>, AppError> {
+        self.pg_repo.list().await
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Category, AppError> {
+        self.pg_repo.get_by_id(id).await
+    }
+
+    pub async fn update(&self, id: Uuid, dto: UpdateCategoryDTO) -> Result<Category, AppError> {
+        self.pg_repo.update(id, dto).await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<(), AppError> {
+        self.pg_repo.delete(id).await
+    }
+}
@@ -1,30 +1,71 @@
-use crate::dtos::employee_dto::{CreateEmployeeDTO, UpdateEmployeeDTO};
+use crate::dtos::employee_dto::{CreateEmployeeDTO, EmployeeListQuery, UpdateEmployeeDTO};
+use crate::dtos::pagination_dto::PageRequest;
 use crate::models::employee::Employee;
+use crate::models::pagination::Page;
+use crate::models::search::SearchHit;
 use crate::repositories::employee_postgres::EmployeePostgresRepo;
+use crate::services::audit_service::AuditService;
+use crate::models::audit::{AuditAction, AuditEntity};
+use std::sync::Arc;
+use crate::errors::AppError;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 pub struct EmployeeService {
     pub pg_repo: EmployeePostgresRepo,
+    pub audit: Arc<AuditService>,
 }
 
 impl EmployeeService {
-    pub async fn add(&self, dto: CreateEmployeeDTO) -> Result<Employee, String> {
-        self.pg_repo.add(dto).await
+    pub async fn add(&self, actor: Uuid, dto: CreateEmployeeDTO) -> Result<Employee, AppError> {
+        let employee = self.pg_repo.add(dto).await?;
+        self.audit.record(Some(actor), AuditAction::Create, AuditEntity::Employee, employee.id, None, Some(&employee)).await;
+        Ok(employee)
     }
     
-    pub async fn get_all(&self) -> Result<Vec<Employee>, String> {
-        self.pg_repo.get_all().await
+    pub async fn list(&self, query: &EmployeeListQuery, page: PageRequest) -> Result<Page<Employee>, AppError> {
+        self.pg_repo.list(query, page).await
     }
     
-    pub async fn get_by_id(&self, id: Uuid) -> Result<Employee, String> {
+    pub async fn search(&self, term: &str, query: &EmployeeListQuery, page: PageRequest) -> Result<Page<SearchHit<Employee>>, AppError> {
+        self.pg_repo.search(term, query, page).await
+    }
+    
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Employee, AppError> {
         self.pg_repo.get_by_id(id).await
     }
     
-    pub async fn update(&self, id: Uuid, dto: UpdateEmployeeDTO) -> Result<Employee, String> {
-        self.pg_repo.update(id, dto).await
+    pub async fn update(&self, actor: Uuid, id: Uuid, dto: UpdateEmployeeDTO) -> Result<Employee, AppError> {
+        let before = self.pg_repo.get_by_id(id).await?;
+        let employee = self.pg_repo.update(id, dto).await?;
+        self.audit.record(Some(actor), AuditAction::Update, AuditEntity::Employee, id, Some(&before), Some(&employee)).await;
+        Ok(employee)
+    }
+    
+    /// Moves the employee to the trash.
+    pub async fn delete(&self, actor: Uuid, id: Uuid) -> Result<(), AppError> {
+        let before = self.pg_repo.get_by_id(id).await?;
+        self.pg_repo.delete(id).await?;
+        self.audit.record(Some(actor), AuditAction::Delete, AuditEntity::Employee, id, Some(&before), None).await;
+        Ok(())
+    }
+    
+    pub async fn trash(&self) -> Result<Vec<Employee>, AppError> {
+        self.pg_repo.trash().await
+    }
+    
+    pub async fn restore(&self, actor: Uuid, id: Uuid) -> Result<Employee, AppError> {
+        let employee = self.pg_repo.restore(id).await?;
+        self.audit.record(Some(actor), AuditAction::Restore, AuditEntity::Employee, id, None, Some(&employee)).await;
+        Ok(employee)
     }
     
-    pub async fn delete(&self, id: Uuid) -> Result<u64, String> {
-        self.pg_repo.delete(id).await
+    /// Permanently deletes employees trashed before `cutoff`.
+    pub async fn purge_trash(&self, cutoff: DateTime<Utc>) -> Result<usize, AppError> {
+        let purged = self.pg_repo.purge(cutoff).await?;
+        for employee in &purged {
+            self.audit.record(None, AuditAction::Purge, AuditEntity::Employee, employee.id, Some(employee), None).await;
+        }
+        Ok(purged.len())
     }
 }
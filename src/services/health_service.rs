@@ -0,0 +1,65 @@
+use crate::models::health::{Check, HealthStatus, Readiness};
+use crate::services::storage::ImageStorage;
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A probe waiting on an exhausted pool must fail before the platform's own probe timeout.
+const DATABASE_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub struct HealthService {
+    pub pool: Pool,
+    pub storage: Arc<dyn ImageStorage>,
+}
+
+impl HealthService {
+    pub async fn readiness(&self) -> Readiness {
+        let database = self.check_database().await;
+        let storage = self.check_storage();
+        let status = if database.status == HealthStatus::Ok && storage.status == HealthStatus::Ok {
+            HealthStatus::Ok
+        } else {
+            HealthStatus::Error
+        };
+        Readiness { status, database, storage }
+    }
+
+    /// Takes a connection from the pool and runs a trivial query on it.
+    async fn check_database(&self) -> Check {
+        let started = Instant::now();
+        let ping = async {
+            let client = self.pool.get().await.map_err(|e| e.to_string())?;
+            client.simple_query("SELECT 1").await.map_err(|e| e.to_string())?;
+            Ok::<_, String>(())
+        };
+        // The probe is public; pool and driver errors can name hosts and users, so they are
+        // only logged
+        let result = match tokio::time::timeout(DATABASE_TIMEOUT, ping).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => {
+                eprintln!("⚠️  Readiness check: database unreachable: {}", e);
+                Err("database unreachable".to_string())
+            }
+            Err(_) => {
+                eprintln!("⚠️  Readiness check: database gave no answer within {} seconds", DATABASE_TIMEOUT.as_secs());
+                Err("timeout".to_string())
+            }
+        };
+        Check {
+            status: if result.is_ok() { HealthStatus::Ok } else { HealthStatus::Error },
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+            backend: None,
+            error: result.err(),
+        }
+    }
+
+    fn check_storage(&self) -> Check {
+        let result = self.storage.check_config();
+        Check {
+            status: if result.is_ok() { HealthStatus::Ok } else { HealthStatus::Error },
+            latency_ms: None,
+            backend: Some(self.storage.name()),
+            error: result.err(),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageEncoder, ImageReader};
+use std::io::Cursor;
+use crate::errors::AppError;
+use crate::services::image_type::ImageFormat;
+
+/// Name of the full-size rendition; its URL is the one stored in `Project.images`.
+pub const ORIGINAL: &str = "original";
+
+/// A downscaled copy generated for every upload, e.g. `thumbnail` at 320px wide.
+#[derive(Debug, Clone)]
+pub struct RenditionSpec {
+    pub name: String,
+    pub max_width: u32,
+}
+
+/// Format renditions are written in. `Original` keeps the uploaded format where possible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Original,
+    WebP,
+    Avif,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImagePipeline {
+    pub renditions: Vec<RenditionSpec>,
+    pub output: OutputFormat,
+    /// Encoder quality from 1 to 100 for JPEG, WebP and AVIF.
+    pub quality: u8,
+}
+
+/// Everything produced for one upload: the original plus its renditions.
+pub struct ProcessedUpload {
+    pub images: Vec<ProcessedImage>,
+    /// Compact placeholder shown while the image loads. `None` for AVIF uploads.
+    pub blurhash: Option<String>,
+}
+
+pub struct ProcessedImage {
+    pub name: String,
+    pub data: Vec<u8>,
+    pub format: ImageFormat,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl ImagePipeline {
+    /// Re-encodes the upload (dropping EXIF and other metadata) and generates the configured
+    /// renditions that are smaller than the upload. CPU heavy: call from a blocking task.
+    pub fn process(&self, data: Vec<u8>, format: ImageFormat) -> Result<ProcessedUpload, AppError> {
+        // AVIF decoding needs the native dav1d library, so AVIF uploads are stored untouched
+        if format == ImageFormat::Avif {
+            return Ok(ProcessedUpload {
+                images: vec![ProcessedImage {
+                    name: ORIGINAL.to_string(),
+                    data,
+                    format,
+                    width: None,
+                    height: None,
+                }],
+                blurhash: None,
+            });
+        }
+
+        let image = decode(&data, format)?;
+        let target = self.target_format(format);
+        let mut processed = Vec::with_capacity(self.renditions.len() + 1);
+
+        // Re-encoding an animated GIF would keep only its first frame
+        let original = if format == ImageFormat::Gif && target == ImageFormat::Png {
+            ProcessedImage {
+                name: ORIGINAL.to_string(),
+                data,
+                format,
+                width: Some(image.width()),
+                height: Some(image.height()),
+            }
+        } else {
+            self.encode(ORIGINAL, &image, target)?
+        };
+        processed.push(original);
+
+        // Renditions at least as wide as the upload would only duplicate the original
+        for spec in self.renditions.iter().filter(|spec| image.width() > spec.max_width) {
+            let resized = image.resize(spec.max_width, u32::MAX, FilterType::Lanczos3);
+            processed.push(self.encode(&spec.name, &resized, target)?);
+        }
+        Ok(ProcessedUpload {
+            images: processed,
+            blurhash: blurhash(&image),
+        })
+    }
+
+    fn target_format(&self, uploaded: ImageFormat) -> ImageFormat {
+        match (self.output, uploaded) {
+            (OutputFormat::WebP, _) => ImageFormat::WebP,
+            (OutputFormat::Avif, _) => ImageFormat::Avif,
+            (OutputFormat::Original, ImageFormat::Gif) => ImageFormat::Png,
+            (OutputFormat::Original, format) => format,
+        }
+    }
+
+    fn encode(&self, name: &str, image: &DynamicImage, format: ImageFormat) -> Result<ProcessedImage, AppError> {
+        let encode_error = |e: image::ImageError| AppError::Internal(format!("Failed to encode {} rendition: {}", name, e));
+        let mut data = Vec::new();
+        match format {
+            ImageFormat::Jpeg => {
+                let rgb = image.to_rgb8();
+                JpegEncoder::new_with_quality(&mut data, self.quality)
+                    .write_image(rgb.as_raw(), rgb.width(), rgb.height(), image::ExtendedColorType::Rgb8)
+                    .map_err(encode_error)?;
+            }
+            ImageFormat::Png | ImageFormat::Gif => {
+                let rgba = image.to_rgba8();
+                PngEncoder::new(&mut data)
+                    .write_image(rgba.as_raw(), rgba.width(), rgba.height(), image::ExtendedColorType::Rgba8)
+                    .map_err(encode_error)?;
+            }
+            ImageFormat::WebP => {
+                let rgba = DynamicImage::ImageRgba8(image.to_rgba8());
+                let encoder = webp::Encoder::from_image(&rgba)
+                    .map_err(|e| AppError::Internal(format!("Failed to encode {} rendition: {}", name, e)))?;
+                data = encoder.encode(self.quality as f32).to_vec();
+            }
+            ImageFormat::Avif => {
+                let rgba = image.to_rgba8();
+                AvifEncoder::new_with_speed_quality(&mut data, 8, self.quality)
+                    .write_image(rgba.as_raw(), rgba.width(), rgba.height(), image::ExtendedColorType::Rgba8)
+                    .map_err(encode_error)?;
+            }
+        }
+        // GIF renditions are written as PNG
+        let format = if format == ImageFormat::Gif { ImageFormat::Png } else { format };
+        Ok(ProcessedImage {
+            name: name.to_string(),
+            data,
+            format,
+            width: Some(image.width()),
+            height: Some(image.height()),
+        })
+    }
+}
+
+/// 4x3 components is the size recommended by blurhash; computed on a small copy because the
+/// cost grows with the pixel count.
+fn blurhash(image: &DynamicImage) -> Option<String> {
+    let small = image.thumbnail(64, 64).to_rgba8();
+    blurhash::encode(4, 3, small.width(), small.height(), small.as_raw()).ok()
+}
+
+/// Decodes the image and applies its EXIF orientation so the stripped copies display upright.
+fn decode(data: &[u8], format: ImageFormat) -> Result<DynamicImage, AppError> {
+    let codec = match format {
+        ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+        ImageFormat::Png => image::ImageFormat::Png,
+        ImageFormat::WebP => image::ImageFormat::WebP,
+        ImageFormat::Gif => image::ImageFormat::Gif,
+        ImageFormat::Avif => image::ImageFormat::Avif,
+    };
+    let invalid = |e: image::ImageError| AppError::UnsupportedMediaType(format!("Image could not be decoded: {}", e));
+
+    let mut decoder = ImageReader::with_format(Cursor::new(data), codec)
+        .into_decoder()
+        .map_err(invalid)?;
+    let orientation = decoder.orientation().map_err(invalid)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(invalid)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
@@ -0,0 +1,142 @@
+/// Image formats accepted for upload, detected from the file's magic bytes rather than the
+/// client-provided name or content type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    WebP,
+    Gif,
+    Avif,
+}
+
+/// Number of leading bytes `sniff` needs to make a decision.
+pub const SNIFF_LEN: usize = 32;
+
+impl ImageFormat {
+    pub fn mime(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::WebP => "image/webp",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::Avif => "image/avif",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::WebP => "webp",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Avif => "avif",
+        }
+    }
+}
+
+pub fn sniff(data: &[u8]) -> Option<ImageFormat> {
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some(ImageFormat::Jpeg);
+    }
+    if data.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        return Some(ImageFormat::Png);
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return Some(ImageFormat::Gif);
+    }
+    if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return Some(ImageFormat::WebP);
+    }
+    if is_avif(data) {
+        return Some(ImageFormat::Avif);
+    }
+    None
+}
+
+/// AVIF files start with an ISO-BMFF `ftyp` box whose major or compatible brands include
+/// `avif` (still image) or `avis` (image sequence).
+fn is_avif(data: &[u8]) -> bool {
+    if data.len() < 16 || &data[4..8] != b"ftyp" {
+        return false;
+    }
+    let box_size = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let end = box_size.min(data.len());
+    let is_avif_brand = |brand: &[u8]| brand == b"avif" || brand == b"avis";
+
+    // Major brand at 8..12, minor version at 12..16, compatible brands after that
+    is_avif_brand(&data[8..12])
+        || data
+            .get(16..end)
+            .map(|brands| brands.chunks_exact(4).any(is_avif_brand))
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An ISO-BMFF `ftyp` box with the given major and compatible brands.
+    fn ftyp(major: &[u8; 4], compatible: &[&[u8; 4]]) -> Vec<u8> {
+        let size = 16 + 4 * compatible.len() as u32;
+        let mut data = size.to_be_bytes().to_vec();
+        data.extend_from_slice(b"ftyp");
+        data.extend_from_slice(major);
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        for brand in compatible {
+            data.extend_from_slice(*brand);
+        }
+        data
+    }
+
+    #[test]
+    fn recognises_every_accepted_format() {
+        let cases: Vec<(Vec<u8>, ImageFormat)> = vec![
+            (vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F'], ImageFormat::Jpeg),
+            (vec![0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x18, b'E', b'x', b'i', b'f'], ImageFormat::Jpeg),
+            (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec(), ImageFormat::Png),
+            (b"GIF87a\x01\0\x01\0".to_vec(), ImageFormat::Gif),
+            (b"GIF89a\x01\0\x01\0".to_vec(), ImageFormat::Gif),
+            (b"RIFF\x24\0\0\0WEBPVP8 ".to_vec(), ImageFormat::WebP),
+            (ftyp(b"avif", &[b"mif1", b"miaf"]), ImageFormat::Avif),
+            (ftyp(b"avis", &[b"msf1"]), ImageFormat::Avif),
+            (ftyp(b"mif1", &[b"miaf", b"avif"]), ImageFormat::Avif),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(sniff(&data), Some(expected), "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn rejects_other_content() {
+        let cases: Vec<Vec<u8>> = vec![
+            // HTML or text uploaded as `photo.png`: only the bytes count, not the name
+            b"<!DOCTYPE html><html><script>alert(1)</script>".to_vec(),
+            b"<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>".to_vec(),
+            b"just some text, definitely not an image".to_vec(),
+            b"%PDF-1.7\n%\xe2\xe3\xcf\xd3".to_vec(),
+            b"RIFF\x24\0\0\0WAVEfmt ".to_vec(),
+            // HEIC shares the container with AVIF but isn't accepted
+            ftyp(b"heic", &[b"mif1", b"heic"]),
+            // A compatible brand past the end of the box doesn't count
+            [ftyp(b"mif1", &[b"miaf"]), b"avif".to_vec()].concat(),
+            Vec::new(),
+        ];
+        for data in cases {
+            assert_eq!(sniff(&data), None, "{:?}", String::from_utf8_lossy(&data));
+        }
+    }
+
+    #[test]
+    fn decides_on_input_shorter_than_sniff_len() {
+        // Tiny files must neither panic nor match on a truncated signature
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\0\x01\0\0\0\x01\x08\x02\0\0\0\x90wS\xde";
+        for len in 0..SNIFF_LEN {
+            let expected = (len >= 8).then_some(ImageFormat::Png);
+            assert_eq!(sniff(&png[..len]), expected, "{} bytes", len);
+        }
+        assert_eq!(sniff(&[0xFF, 0xD8]), None);
+        assert_eq!(sniff(b"GIF89"), None);
+        assert_eq!(sniff(b"RIFF\x24\0\0\0WEB"), None);
+        assert_eq!(sniff(&ftyp(b"avif", &[])[..15]), None);
+    }
+}
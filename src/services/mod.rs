@@ -1,3 +1,10 @@
+pub mod api_key_service;
+pub mod audit_service;
+pub mod auth_service;
+pub mod category_service;
 pub mod employee_service;
+pub mod health_service;
+pub mod image_processing;
+pub mod image_type;
 pub mod project_service;
-pub mod cloudinary;
\ No newline at end of file
+pub mod storage;
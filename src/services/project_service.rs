@@ -1,34 +1,235 @@
-use crate::dtos::project_dto::{CreateProjectDTO, UpdateProjectDTO};
-use crate::models::project::Project;
+use crate::dtos::project_dto::{CreateProjectDTO, NewProjectImageDTO, ProjectListQuery, ReorderImagesDTO, UpdateProjectDTO, UpdateProjectImageDTO};
+use crate::dtos::pagination_dto::PageRequest;
+use crate::models::project::{Project, ProjectImage, Rendition, RenditionSet, SlugLookup};
+use crate::models::pagination::Page;
+use crate::models::search::SearchHit;
+use crate::models::tag::TagCount;
 use crate::repositories::project_postgres::ProjectPostgresRepo;
+use crate::services::storage::ImageStorage;
+use crate::services::image_processing::{ImagePipeline, ORIGINAL};
+use crate::services::image_type::ImageFormat;
+use crate::services::audit_service::AuditService;
+use crate::models::audit::{AuditAction, AuditEntity};
+use crate::models::revision::{ProjectChange, ProjectContent, ProjectRevision, RevisionDiff};
+use crate::services::audit_service::diff;
+use crate::errors::AppError;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
 use uuid::Uuid;
 
 pub struct ProjectService {
     pub pg_repo: ProjectPostgresRepo,
+    pub storage: Arc<dyn ImageStorage>,
+    pub pipeline: Arc<ImagePipeline>,
+    pub audit: Arc<AuditService>,
 }
 
 impl ProjectService {
-    pub async fn add(&self, dto: CreateProjectDTO) -> Result<Project, String> {
-        self.pg_repo.add(dto).await
+    pub async fn add(&self, actor: Uuid, dto: CreateProjectDTO) -> Result<Project, AppError> {
+        let project = self.pg_repo.add(dto).await?;
+        self.audit.record(Some(actor), AuditAction::Create, AuditEntity::Project, project.id, None, Some(&project)).await;
+        Ok(project)
     }
     
-    pub async fn get_all(&self) -> Result<Vec<Project>, String> {
-        self.pg_repo.get_all().await
+    pub async fn list(&self, query: &ProjectListQuery, page: PageRequest) -> Result<Page<Project>, AppError> {
+        self.pg_repo.list(query, page).await
     }
     
-    pub async fn get_by_category(&self, category: String) -> Result<Vec<Project>, String> {
-        self.pg_repo.get_by_category(category).await
+    pub async fn search(&self, term: &str, query: &ProjectListQuery, page: PageRequest) -> Result<Page<SearchHit<Project>>, AppError> {
+        self.pg_repo.search(term, query, page).await
     }
     
-    pub async fn get_by_id(&self, id: Uuid) -> Result<Project, String> {
+    pub async fn publish_scheduled(&self) -> Result<Vec<Uuid>, AppError> {
+        let published = self.pg_repo.publish_scheduled().await?;
+        for id in &published {
+            let changes = serde_json::json!({ "status": { "before": "draft", "after": "published" } });
+            self.audit.record_changes(None, AuditAction::Publish, AuditEntity::Project, *id, changes).await;
+        }
+        Ok(published)
+    }
+    
+    pub async fn tag_cloud(&self) -> Result<Vec<TagCount>, AppError> {
+        self.pg_repo.tag_cloud().await
+    }
+    
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Project, AppError> {
         self.pg_repo.get_by_id(id).await
     }
     
-    pub async fn update(&self, id: Uuid, dto: UpdateProjectDTO) -> Result<Project, String> {
-        self.pg_repo.update(id, dto).await
+    pub async fn get_by_slug(&self, slug: &str) -> Result<SlugLookup, AppError> {
+        self.pg_repo.get_by_slug(slug).await
+    }
+    
+    pub async fn update(&self, actor: Uuid, id: Uuid, dto: UpdateProjectDTO) -> Result<Project, AppError> {
+        let change = self.pg_repo.update(actor, id, dto).await?;
+        Ok(self.record_change(actor, change).await)
     }
     
-    pub async fn delete(&self, id: Uuid) -> Result<u64, String> {
-        self.pg_repo.delete(id).await
+    /// Moves the project to the trash; its images are kept until it is purged.
+    pub async fn delete(&self, actor: Uuid, id: Uuid) -> Result<(), AppError> {
+        let before = self.pg_repo.get_by_id(id).await?;
+        self.pg_repo.delete(id).await?;
+        self.audit.record(Some(actor), AuditAction::Delete, AuditEntity::Project, id, Some(&before), None).await;
+        Ok(())
+    }
+
+    pub async fn trash(&self) -> Result<Vec<Project>, AppError> {
+        self.pg_repo.trash().await
+    }
+
+    pub async fn restore(&self, actor: Uuid, id: Uuid) -> Result<Project, AppError> {
+        let project = self.pg_repo.restore(id).await?;
+        self.audit.record(Some(actor), AuditAction::Restore, AuditEntity::Project, id, None, Some(&project)).await;
+        Ok(project)
+    }
+
+    /// Permanently deletes projects trashed before `cutoff` together with their stored images.
+    pub async fn purge_trash(&self, cutoff: DateTime<Utc>) -> Result<usize, AppError> {
+        let (purged, orphaned_assets) = self.pg_repo.purge(cutoff).await?;
+        for project in &purged {
+            self.audit.record(None, AuditAction::Purge, AuditEntity::Project, project.id, Some(project), None).await;
+        }
+        self.delete_assets(orphaned_assets).await;
+        Ok(purged.len())
+    }
+
+    /// Revisions of a project, newest first.
+    pub async fn revisions(&self, project_id: Uuid) -> Result<Vec<ProjectRevision>, AppError> {
+        self.pg_repo.get_by_id(project_id).await?;
+        self.pg_repo.revisions(project_id).await
+    }
+
+    pub async fn revision(&self, project_id: Uuid, revision: i32) -> Result<ProjectRevision, AppError> {
+        self.pg_repo.revision(project_id, revision).await
+    }
+
+    /// What changed from `revision` to `against`, or to the current content when `against` is `None`.
+    pub async fn diff_revision(&self, project_id: Uuid, revision: i32, against: Option<i32>) -> Result<RevisionDiff, AppError> {
+        let from = self.pg_repo.revision(project_id, revision).await?.content;
+        let to = match against {
+            Some(other) => self.pg_repo.revision(project_id, other).await?.content,
+            None => ProjectContent::from(&self.pg_repo.get_by_id(project_id).await?),
+        };
+        let to_value = |content: &ProjectContent| serde_json::to_value(content).map_err(|e| AppError::Internal(e.to_string()));
+        Ok(RevisionDiff {
+            revision,
+            against,
+            changes: diff(&to_value(&from)?, &to_value(&to)?),
+        })
+    }
+
+    /// Reverts the content of the project to `revision`. The content being replaced becomes
+    /// a new revision itself, so a restore can be undone.
+    pub async fn restore_revision(&self, actor: Uuid, project_id: Uuid, revision: i32) -> Result<Project, AppError> {
+        let revision = self.pg_repo.revision(project_id, revision).await?;
+        let change = self.pg_repo.restore_content(actor, project_id, revision.content).await?;
+        Ok(self.record_change(actor, change).await)
+    }
+
+    pub async fn add_image(&self, actor: Uuid, project_id: Uuid, dto: NewProjectImageDTO) -> Result<ProjectImage, AppError> {
+        let (image, change) = self.pg_repo.add_image(actor, project_id, dto).await?;
+        self.record_change(actor, change).await;
+        Ok(image)
+    }
+
+    pub async fn update_image(&self, actor: Uuid, project_id: Uuid, image_id: Uuid, dto: UpdateProjectImageDTO) -> Result<ProjectImage, AppError> {
+        if dto.is_cover == Some(false) {
+            return Err(AppError::Validation(
+                "is_cover can only be set to true; mark another image as cover instead".to_string(),
+            ));
+        }
+        let (image, change) = self.pg_repo.update_image(actor, project_id, image_id, dto).await?;
+        self.record_change(actor, change).await;
+        Ok(image)
+    }
+
+    pub async fn reorder_images(&self, actor: Uuid, project_id: Uuid, dto: ReorderImagesDTO) -> Result<Vec<ProjectImage>, AppError> {
+        let change = self.pg_repo.reorder_images(actor, project_id, dto).await?;
+        Ok(self.record_change(actor, change).await.images)
+    }
+
+    /// The files stay in storage: the previous revision still refers to them. They are
+    /// deleted when the project is purged from the trash.
+    pub async fn remove_image(&self, actor: Uuid, project_id: Uuid, image_id: Uuid) -> Result<(), AppError> {
+        let (_, change) = self.pg_repo.remove_image(actor, project_id, image_id).await?;
+        self.record_change(actor, change).await;
+        Ok(())
+    }
+
+    /// Writes the audit entry of an update and returns the updated project. The revision
+    /// was already recorded by the repository, in the transaction of the update.
+    async fn record_change(&self, actor: Uuid, change: ProjectChange) -> Project {
+        let ProjectChange { before, after } = change;
+        self.audit.record(Some(actor), AuditAction::Update, AuditEntity::Project, before.id, Some(&before), Some(&after)).await;
+        after
+    }
+
+    /// Runs the upload through the image pipeline and stores every rendition as
+    /// `<basename>[_<rendition>].<ext>`. The result can be attached to a project as is.
+    pub async fn upload_image(&self, data: Vec<u8>, format: ImageFormat, basename: &str) -> Result<NewProjectImageDTO, AppError> {
+        let pipeline = self.pipeline.clone();
+        let processed = tokio::task::spawn_blocking(move || pipeline.process(data, format))
+            .await
+            .map_err(|e| AppError::Internal(format!("Image processing task failed: {}", e)))??;
+
+        let mut renditions = RenditionSet::new();
+        for image in processed.images {
+            let filename = if image.name == ORIGINAL {
+                format!("{}.{}", basename, image.format.extension())
+            } else {
+                format!("{}_{}.{}", basename, image.name, image.format.extension())
+            };
+            match self.storage.store(image.data, &filename, image.format.mime()).await {
+                Ok(url) => {
+                    renditions.insert(image.name, Rendition { url, width: image.width, height: image.height });
+                }
+                Err(e) => {
+                    // Don't leave half of the renditions behind
+                    self.delete_assets(renditions.into_values().map(|r| r.url)).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        let original = renditions
+            .get(ORIGINAL)
+            .cloned()
+            .ok_or_else(|| AppError::Internal("Image pipeline produced no original rendition".to_string()))?;
+        // Small uploads serve the original for every rendition that was skipped
+        for spec in &self.pipeline.renditions {
+            renditions.entry(spec.name.clone()).or_insert_with(|| original.clone());
+        }
+        Ok(NewProjectImageDTO {
+            url: original.url,
+            width: original.width,
+            height: original.height,
+            blurhash: processed.blurhash,
+            renditions,
+            ..Default::default()
+        })
+    }
+
+    /// Deletes files of uploads that end up not being attached to a project.
+    pub async fn discard_uploads(&self, images: &[NewProjectImageDTO]) {
+        let urls = images.iter().flat_map(|image| {
+            std::iter::once(image.url.clone()).chain(image.renditions.values().map(|r| r.url.clone()))
+        });
+        let mut unique: Vec<String> = urls.collect();
+        unique.sort();
+        unique.dedup();
+        self.delete_assets(unique).await;
+    }
+
+    /// Best effort: the database change is already committed, so a failed asset delete is only logged.
+    async fn delete_assets<I, S>(&self, urls: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for url in urls {
+            if let Err(e) = self.storage.delete(url.as_ref()).await {
+                eprintln!("⚠️  Failed to delete image {} from {} storage: {}", url.as_ref(), self.storage.name(), e);
+            }
+        }
     }
 }
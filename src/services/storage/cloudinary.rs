@@ -0,0 +1,197 @@
+use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use crate::errors::AppError;
+use super::{upstream_error, ImageStorage};
+
+const FOLDER: &str = "portfolio";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloudinaryResponse {
+    pub secure_url: String,
+    pub public_id: String,
+}
+
+/// API credentials are only needed to delete assets; uploads use the unsigned preset.
+pub struct CloudinaryCredentials {
+    pub api_key: String,
+    pub api_secret: String,
+}
+
+pub struct CloudinaryStorage {
+    cloud_name: String,
+    upload_preset: String,
+    credentials: Option<CloudinaryCredentials>,
+    client: reqwest::Client,
+}
+
+impl CloudinaryStorage {
+    pub fn new(cloud_name: String, upload_preset: String, credentials: Option<CloudinaryCredentials>) -> Self {
+        Self {
+            cloud_name,
+            upload_preset,
+            credentials,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Extracts the public id (`portfolio/abc`) from a delivery URL such as
+    /// `https://res.cloudinary.com/<cloud>/image/upload/c_fill,w_300/v123/portfolio/abc.png`.
+    /// Transformation and version segments are dropped; assets outside `FOLDER` are never
+    /// ours to delete.
+    fn public_id(&self, url: &str) -> Option<String> {
+        let prefix = format!("https://res.cloudinary.com/{}/image/upload/", self.cloud_name);
+        let segments: Vec<&str> = url.strip_prefix(&prefix)?.split('/').collect();
+        let start = segments.iter().position(|segment| *segment == FOLDER)?;
+        if !segments[..start].iter().all(|segment| is_version(segment) || is_transformation(segment)) {
+            return None;
+        }
+        let path = segments[start..].join("/");
+        let public_id = path.rsplit_once('.').map(|(id, _)| id).unwrap_or(&path);
+        (public_id.len() > FOLDER.len() + 1).then(|| public_id.to_string())
+    }
+}
+
+/// `v1712345678`
+fn is_version(segment: &str) -> bool {
+    segment
+        .strip_prefix('v')
+        .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// `c_fill,w_300,q_auto`: comma-separated parameters, each a short lowercase key, `_` and a value.
+fn is_transformation(segment: &str) -> bool {
+    segment.split(',').all(|param| {
+        param.split_once('_').is_some_and(|(key, value)| {
+            (1..=3).contains(&key.len()) && key.chars().all(|c| c.is_ascii_lowercase()) && !value.is_empty()
+        })
+    })
+}
+
+#[async_trait]
+impl ImageStorage for CloudinaryStorage {
+    async fn store(&self, data: Vec<u8>, filename: &str, content_type: &str) -> Result<String, AppError> {
+        let url = format!(
+            "https://api.cloudinary.com/v1_1/{}/image/upload",
+            self.cloud_name
+        );
+
+        let part = Part::bytes(data)
+            .file_name(filename.to_string())
+            .mime_str(content_type)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let form = Form::new()
+            .part("file", part)
+            .text("upload_preset", self.upload_preset.clone())
+            .text("folder", FOLDER);
+
+        let response = self.client
+            .post(&url)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| upstream_error("Failed to upload to Cloudinary", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(upstream_error(&format!("Cloudinary upload failed with {}", status), error_text));
+        }
+
+        let cloudinary_response: CloudinaryResponse = response
+            .json()
+            .await
+            .map_err(|e| upstream_error("Failed to parse Cloudinary response", e))?;
+
+        Ok(cloudinary_response.secure_url)
+    }
+
+    async fn delete(&self, url: &str) -> Result<(), AppError> {
+        let Some(public_id) = self.public_id(url) else {
+            return Ok(());
+        };
+        let Some(credentials) = &self.credentials else {
+            return Err(AppError::Internal(
+                "Cannot delete Cloudinary asset: CLOUDINARY_API_KEY and CLOUDINARY_API_SECRET are not set".to_string(),
+            ));
+        };
+
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let signature = hex::encode(Sha1::digest(format!(
+            "public_id={}&timestamp={}{}",
+            public_id, timestamp, credentials.api_secret
+        )));
+
+        let response = self.client
+            .post(format!("https://api.cloudinary.com/v1_1/{}/image/destroy", self.cloud_name))
+            .form(&[
+                ("public_id", public_id.as_str()),
+                ("timestamp", timestamp.as_str()),
+                ("api_key", credentials.api_key.as_str()),
+                ("signature", signature.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| upstream_error("Failed to delete from Cloudinary", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(upstream_error(&format!("Cloudinary delete failed with {}", status), error_text));
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "cloudinary"
+    }
+
+    fn check_config(&self) -> Result<(), String> {
+        if self.cloud_name.trim().is_empty() {
+            return Err("CLOUDINARY_CLOUD_NAME is empty".to_string());
+        }
+        if self.upload_preset.trim().is_empty() {
+            return Err("CLOUDINARY_UPLOAD_PRESET is empty".to_string());
+        }
+        match &self.credentials {
+            Some(c) if c.api_key.trim().is_empty() || c.api_secret.trim().is_empty() => {
+                Err("CLOUDINARY_API_KEY and CLOUDINARY_API_SECRET must not be empty".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn public_id_drops_transformations_and_version() {
+        let storage = CloudinaryStorage::new("demo".to_string(), "preset".to_string(), None);
+        for url in [
+            "https://res.cloudinary.com/demo/image/upload/portfolio/abc.png",
+            "https://res.cloudinary.com/demo/image/upload/v1712345678/portfolio/abc.png",
+            "https://res.cloudinary.com/demo/image/upload/c_fill,w_300,h_200/portfolio/abc.webp",
+            "https://res.cloudinary.com/demo/image/upload/a_90/c_fill,w_300/v12/portfolio/abc.jpg",
+        ] {
+            assert_eq!(storage.public_id(url).as_deref(), Some("portfolio/abc"), "{}", url);
+        }
+    }
+
+    #[test]
+    fn public_id_ignores_foreign_assets() {
+        let storage = CloudinaryStorage::new("demo".to_string(), "preset".to_string(), None);
+        for url in [
+            "https://res.cloudinary.com/other/image/upload/v1/portfolio/abc.png",
+            "https://res.cloudinary.com/demo/image/upload/v1/avatars/abc.png",
+            "https://res.cloudinary.com/demo/image/upload/avatars/portfolio/abc.png",
+            "https://res.cloudinary.com/demo/image/upload/v1/portfolio",
+            "https://example.com/portfolio/abc.png",
+        ] {
+            assert_eq!(storage.public_id(url), None, "{}", url);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+use crate::errors::AppError;
+use super::{stored_file_name, ImageStorage};
+
+/// Stores images on the local filesystem; they are served by the `/uploads` static route.
+pub struct LocalStorage {
+    dir: PathBuf,
+    url_prefix: String,
+}
+
+impl LocalStorage {
+    pub fn new(dir: impl Into<PathBuf>, url_prefix: &str) -> Result<Self, AppError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AppError::Storage(format!("Cannot create upload directory {}: {}", dir.display(), e)))?;
+        Ok(Self {
+            dir,
+            url_prefix: url_prefix.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl ImageStorage for LocalStorage {
+    async fn store(&self, data: Vec<u8>, filename: &str, _content_type: &str) -> Result<String, AppError> {
+        let filename = sanitize_filename::sanitize(filename);
+        let path = self.dir.join(&filename);
+        tokio::fs::write(&path, data)
+            .await
+            .map_err(|e| AppError::Storage(format!("Error writing file: {}", e)))?;
+        Ok(format!("{}/{}", self.url_prefix, filename))
+    }
+
+    async fn delete(&self, url: &str) -> Result<(), AppError> {
+        let Some(filename) = url
+            .strip_prefix(&format!("{}/", self.url_prefix))
+            .and_then(stored_file_name)
+        else {
+            return Ok(());
+        };
+        let path = self.dir.join(filename);
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::Storage(format!("Error deleting file: {}", e))),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "local"
+    }
+}
@@ -0,0 +1,53 @@
+pub mod cloudinary;
+pub mod local;
+pub mod s3;
+
+use async_trait::async_trait;
+use crate::errors::AppError;
+
+/// Backend that stores uploaded images and serves them from a public URL.
+///
+/// Projects only keep the returned URLs, so each backend must be able to recognise its own
+/// URLs again in `delete`.
+#[async_trait]
+pub trait ImageStorage: Send + Sync {
+    /// Stores `data` under `filename` and returns the public URL of the stored image.
+    async fn store(&self, data: Vec<u8>, filename: &str, content_type: &str) -> Result<String, AppError>;
+
+    /// Removes the asset behind `url`. URLs not owned by this backend are ignored.
+    async fn delete(&self, url: &str) -> Result<(), AppError>;
+
+    fn name(&self) -> &'static str;
+
+    /// Problems with the backend's configuration, reported by `GET /readyz`. Must not call
+    /// the backend itself: readiness probes run every few seconds.
+    fn check_config(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Provider errors can name buckets, keys, accounts and signatures, so the detail is only
+/// logged; clients get a fixed message.
+fn upstream_error(context: &str, detail: impl std::fmt::Display) -> AppError {
+    eprintln!("⚠️  {}: {}", context, detail);
+    AppError::Upstream("Image storage request failed".to_string())
+}
+
+/// `name` if it is a single file name as `store` would have written it, so a crafted URL
+/// can't reach outside the upload location (`..`, nested paths, ...).
+fn stored_file_name(name: &str) -> Option<&str> {
+    (!name.is_empty() && sanitize_filename::sanitize(name) == name && name != "." && name != "..").then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_plain_file_names_are_stored_names() {
+        assert_eq!(stored_file_name("1712345678-cover.webp"), Some("1712345678-cover.webp"));
+        for name in ["", ".", "..", "../main.rs", "a/b.png", "a\\b.png"] {
+            assert_eq!(stored_file_name(name), None, "{}", name);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use crate::errors::AppError;
+use super::{stored_file_name, upstream_error, ImageStorage};
+
+const KEY_PREFIX: &str = "portfolio";
+
+#[derive(Clone)]
+pub struct S3Config {
+    /// e.g. `https://s3.ap-southeast-1.amazonaws.com`, `https://<account>.r2.cloudflarestorage.com`
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Base URL the bucket is publicly served from, e.g. a CDN domain.
+    pub public_url: String,
+}
+
+/// S3-compatible object storage (AWS S3, Cloudflare R2, MinIO, ...) using path-style requests
+/// signed with AWS Signature Version 4.
+pub struct S3Storage {
+    config: S3Config,
+    client: reqwest::Client,
+}
+
+impl S3Storage {
+    pub fn new(mut config: S3Config) -> Self {
+        config.endpoint = config.endpoint.trim_end_matches('/').to_string();
+        config.public_url = config.public_url.trim_end_matches('/').to_string();
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// The object key behind `url`, only for objects under `KEY_PREFIX` as written by `store`;
+    /// anything else in the bucket is never touched.
+    fn key(&self, url: &str) -> Option<String> {
+        let filename = url
+            .strip_prefix(&format!("{}/{}/", self.config.public_url, KEY_PREFIX))
+            .and_then(stored_file_name)?;
+        Some(format!("{}/{}", KEY_PREFIX, filename))
+    }
+
+    async fn send(&self, method: reqwest::Method, key: &str, body: Vec<u8>, content_type: Option<&str>) -> Result<reqwest::Response, AppError> {
+        let path = format!("/{}/{}", self.config.bucket, key);
+        let url = reqwest::Url::parse(&format!("{}{}", self.config.endpoint, uri_encode_path(&path)))
+            .map_err(|e| AppError::Internal(format!("Invalid S3 endpoint: {}", e)))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(AppError::Internal("Invalid S3 endpoint: missing host".to_string())),
+        };
+
+        let now = chrono::Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method.as_str(),
+            url.path(),
+            host,
+            payload_hash,
+            amz_date,
+            payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.config.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let mut key_bytes = hmac(format!("AWS4{}", self.config.secret_access_key).as_bytes(), date.as_bytes());
+        for part in [self.config.region.as_str(), "s3", "aws4_request"] {
+            key_bytes = hmac(&key_bytes, part.as_bytes());
+        }
+        let signature = hex::encode(hmac(&key_bytes, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+            self.config.access_key_id, scope, signature
+        );
+
+        let mut request = self.client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization);
+        if let Some(content_type) = content_type {
+            request = request.header("content-type", content_type);
+        }
+        request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| upstream_error("S3 request failed", e))
+    }
+}
+
+#[async_trait]
+impl ImageStorage for S3Storage {
+    async fn store(&self, data: Vec<u8>, filename: &str, content_type: &str) -> Result<String, AppError> {
+        let key = format!("{}/{}", KEY_PREFIX, sanitize_filename::sanitize(filename));
+        let response = self.send(reqwest::Method::PUT, &key, data, Some(content_type)).await?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(upstream_error(&format!("S3 upload failed with {}", status), error_text));
+        }
+        Ok(format!("{}/{}", self.config.public_url, key))
+    }
+
+    async fn delete(&self, url: &str) -> Result<(), AppError> {
+        let Some(key) = self.key(url) else {
+            return Ok(());
+        };
+        let response = self.send(reqwest::Method::DELETE, &key, Vec::new(), None).await?;
+        // S3 answers 204 for deletes, including keys that no longer exist
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(upstream_error(&format!("S3 delete failed with {}", status), error_text));
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encodes everything except unreserved characters and `/`, as SigV4 requires.
+fn uri_encode_path(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_keys_under_the_prefix_are_deleted() {
+        let storage = S3Storage::new(S3Config {
+            endpoint: "https://s3.example.com".to_string(),
+            region: "auto".to_string(),
+            bucket: "media".to_string(),
+            access_key_id: "id".to_string(),
+            secret_access_key: "secret".to_string(),
+            public_url: "https://cdn.example.com/".to_string(),
+        });
+        assert_eq!(storage.key("https://cdn.example.com/portfolio/abc.png").as_deref(), Some("portfolio/abc.png"));
+        for url in [
+            "https://cdn.example.com/backups/db.sql",
+            "https://cdn.example.com/portfolio/../backups/db.sql",
+            "https://cdn.example.com/portfolio/nested/abc.png",
+            "https://cdn.example.com/portfolio/",
+            "https://other.example.com/portfolio/abc.png",
+        ] {
+            assert_eq!(storage.key(url), None, "{}", url);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+/// URL-friendly form of a display name: `"Node.js"` -> `node-js`, `"C++"` -> `c-plus-plus`.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    let mut pending_dash = false;
+    for c in text.trim().chars() {
+        let word = match c {
+            '+' => Some("plus"),
+            '#' => Some("sharp"),
+            _ => None,
+        };
+        if let Some(word) = word {
+            if !slug.is_empty() {
+                slug.push('-');
+            }
+            slug.push_str(word);
+            pending_dash = true;
+        } else if c.is_alphanumeric() {
+            if pending_dash && !slug.is_empty() {
+                slug.push('-');
+            }
+            slug.extend(c.to_lowercase());
+            pending_dash = false;
+        } else {
+            pending_dash = true;
+        }
+    }
+    slug
+}
@@ -0,0 +1,96 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use crate::slug::slugify;
+use validator::{ValidateUrl, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+/// Field path (`name`, `images[0].url`) -> human readable problems, returned with 422 responses.
+pub type FieldErrors = BTreeMap<String, Vec<String>>;
+
+/// Rejects strings that are empty once surrounding whitespace is removed.
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new("blank").with_message(Cow::from("must not be blank")));
+    }
+    Ok(())
+}
+
+/// Lowercase letters and digits separated by single `_` or `-`, e.g. `web_development`.
+pub fn slug(value: &str) -> Result<(), ValidationError> {
+    let valid = !value.is_empty()
+        && value.split(['_', '-']).all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        });
+    if valid {
+        return Ok(());
+    }
+    Err(ValidationError::new("slug").with_message(Cow::from(
+        "must be lowercase letters and digits separated by _ or -",
+    )))
+}
+
+/// Every tag name must be non-blank, at most 50 characters and contain a letter or digit.
+pub fn tag_names(names: &[String]) -> Result<(), ValidationError> {
+    let valid = names
+        .iter()
+        .all(|name| name.trim().chars().count() <= 50 && !slugify(name).is_empty());
+    if valid {
+        return Ok(());
+    }
+    Err(ValidationError::new("tags").with_message(Cow::from(
+        "each tag must be 1-50 characters and contain a letter or digit",
+    )))
+}
+
+/// Accepts absolute `http(s)` URLs and root-relative paths such as `/uploads/x.jpg`
+/// (what local storage returns).
+pub fn image_url(value: &str) -> Result<(), ValidationError> {
+    let relative = value.starts_with('/') && !value.starts_with("//") && !value.contains(char::is_whitespace);
+    let absolute = (value.starts_with("https://") || value.starts_with("http://")) && value.validate_url();
+    if relative || absolute {
+        return Ok(());
+    }
+    Err(ValidationError::new("url").with_message(Cow::from("must be an http(s) URL or a path starting with /")))
+}
+
+/// Flattens nested validator errors into `FieldErrors`.
+pub fn field_errors(errors: &ValidationErrors) -> FieldErrors {
+    let mut out = FieldErrors::new();
+    collect(errors, "", &mut out);
+    out
+}
+
+fn collect(errors: &ValidationErrors, prefix: &str, out: &mut FieldErrors) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() { field.to_string() } else { format!("{}.{}", prefix, field) };
+        match kind {
+            ValidationErrorsKind::Field(list) => {
+                out.entry(path).or_default().extend(list.iter().map(describe));
+            }
+            ValidationErrorsKind::Struct(nested) => collect(nested, &path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect(nested, &format!("{}[{}]", path, index), out);
+                }
+            }
+        }
+    }
+}
+
+fn describe(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+    let param = |name: &str| error.params.get(name).and_then(|v| v.as_u64());
+    match error.code.as_ref() {
+        "length" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => format!("must be between {} and {} characters", min, max),
+            (Some(min), None) => format!("must be at least {} characters", min),
+            (None, Some(max)) => format!("must be at most {} characters", max),
+            (None, None) => "has an invalid length".to_string(),
+        },
+        "email" => "must be a valid email address".to_string(),
+        "url" => "must be a valid URL".to_string(),
+        "range" => "is out of range".to_string(),
+        code => format!("is invalid ({})", code),
+    }
+}